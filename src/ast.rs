@@ -1,21 +1,4 @@
-use std::{convert::Infallible, marker::PhantomData};
-
-use crate::{Ident, Primitive, TAtomKind};
-
-enum TypeScalarAnnotation {
-    Base(TAtomKind),
-    Rec(TypeAnnotation),
-}
-struct TypeAnnotation {
-    scalar_type: Box<TypeScalarAnnotation>, // The type
-    shape: ShapeDescription,                // And the shape
-}
-
-/// TODO: Indicate properly, as a struct, that we may know rank and length and such
-enum ShapeDescription {
-    Rank(usize),                                // We know the number of axis
-    Axes(Vec<(Option<String>, Option<usize>)>), // We may know the size or name of each axis. Implies rank
-}
+use std::marker::PhantomData;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Sp<'src> {
@@ -36,30 +19,13 @@ impl<'a> Sp<'a> {
             _phantom: PhantomData,
         }
     }
-    pub fn merge<'src>(&'src self, rhs: &'src Sp) -> Sp<'src> {
-        Self::new(self.start.min(rhs.start), self.start.max(rhs.start))
+    pub fn merge(self, rhs: Sp<'a>) -> Sp<'a> {
+        Self::new(self.start.min(rhs.start), self.end.max(rhs.end))
+    }
+    pub fn slice(self, src: &'a str) -> &'a str {
+        &src[self.start..self.end]
+    }
+    pub fn to_static(self) -> Sp<'static> {
+        Sp::new(self.start, self.end)
     }
-}
-
-/// A word, directly as read in
-pub enum Word {
-    // Nat -> Int -> Float
-    Number(String),
-    String(String),
-    Array(Infallible),
-    Primitive(Primitive),
-}
-
-/// What appears in the code, pretty much at any location
-pub enum Item<'src> {
-    TypeDef {
-        left: Option<TypeAnnotation>,
-        right: Option<TypeAnnotation>,
-        out: Option<TypeAnnotation>,
-    },
-    Binding {
-        name: Ident,
-        code: Vec<(Word, Sp<'src>)>,
-    },
-    Words(Vec<(Word, Sp<'src>)>),
 }
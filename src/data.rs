@@ -0,0 +1,86 @@
+//! User-defined types, declared with `□ Name ← ⟨ ... ⟩`. Product types are sum types with a single
+//! unnamed variant, so only the latter exist past the lexer
+
+use std::rc::Rc;
+
+use crate::{primitive::frame_and_cells, *};
+
+/// A declaration as written, before the types of its fields are looked up
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DataSyntax {
+    pub(crate) name: String,
+    pub(crate) variants: Vec<VariantSyntax>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct VariantSyntax {
+    /// `None` for the variant of a product type, spelt `_`
+    pub(crate) name: Option<String>,
+    pub(crate) fields: Vec<(String, FieldType)>,
+}
+
+/// `'Name` or `'(rank, Name)`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum FieldType {
+    Named(String),
+    Array { rank: u32, scalar: String },
+}
+
+/// Which declaration a value comes from, in the order they were made
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct DataId(pub(crate) u32);
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct DataDecl {
+    pub(crate) name: String,
+    pub(crate) id: DataId,
+    pub(crate) variants: Vec<Variant>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Variant {
+    pub(crate) name: Option<String>,
+    pub(crate) fields: Vec<(String, Known)>,
+}
+
+impl DataDecl {
+    /// The name a variant is built with. That of a product type is the type's own
+    pub(crate) fn constructor(&self, variant: usize) -> &str {
+        self.variants[variant].name.as_deref().unwrap_or(&self.name)
+    }
+    /// The variant built by `name`
+    pub(crate) fn variant(&self, name: &str) -> Option<usize> {
+        (0..self.variants.len()).find(|&v| self.constructor(v) == name)
+    }
+}
+
+/// Builds a value of a variant with fields. A single field is the argument itself, several are its
+/// major cells
+pub(crate) fn construct(decl: &Rc<DataDecl>, variant: usize, x: TNoun) -> Result<TNoun, TErrorKind> {
+    let fields = match decl.variants[variant].fields.len() {
+        1 => vec![x],
+        n => match frame_and_cells(x) {
+            (frame, cells) if frame == [n as u32] => cells,
+            _ => return Err(TErrorKind::Length),
+        },
+    };
+    Ok(TNoun::Atom(TAtom::SumType(TSumType {
+        decl: decl.clone(),
+        variant,
+        fields,
+    })))
+}
+
+/// `x .name`. Only the variant `x` is of matters, others of the same type may not have the field
+pub(crate) fn field(x: &TNoun, name: &str) -> Result<TNoun, TErrorKind> {
+    match x {
+        TNoun::Atom(TAtom::SumType(v)) => v.decl.variants[v.variant]
+            .fields
+            .iter()
+            .position(|(f, _)| f == name)
+            .map(|i| v.fields[i].clone())
+            .ok_or(TErrorKind::Domain),
+        TNoun::Atom(_) => Err(TErrorKind::Domain),
+        TNoun::Array(_) => Err(TErrorKind::Rank),
+    }
+}
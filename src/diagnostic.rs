@@ -0,0 +1,331 @@
+//! Errors as they're shown to people: what went wrong, where, and what might be done about it. The
+//! source lines are printed with the spans underlined, which takes some care as most glyphs are
+//! several bytes long and some characters are two columns wide
+
+use std::fmt::Write;
+
+use crate::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A span of the source along with what it has to do with the problem
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Label<'src> {
+    pub span: Sp<'src>,
+    pub message: String,
+    /// Whether this is where the problem is, rather than something related to it
+    pub primary: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic<'src> {
+    pub severity: Severity,
+    /// See [`TErrorKind::code`]
+    pub code: Option<&'static str>,
+    pub message: String,
+    /// The primary label comes first
+    pub labels: Vec<Label<'src>>,
+    pub notes: Vec<String>,
+    pub help: Option<String>,
+}
+
+impl<'src> Diagnostic<'src> {
+    pub fn error(message: impl Into<String>, span: Sp<'src>, label: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            code: None,
+            message: message.into(),
+            labels: vec![Label {
+                span,
+                message: label.into(),
+                primary: true,
+            }],
+            notes: vec![],
+            help: None,
+        }
+    }
+    pub fn with_code(mut self, code: &'static str) -> Self {
+        self.code = Some(code);
+        self
+    }
+    pub fn with_label(mut self, span: Sp<'src>, message: impl Into<String>) -> Self {
+        self.labels.push(Label {
+            span,
+            message: message.into(),
+            primary: false,
+        });
+        self
+    }
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    /// The diagnostic with the lines of `src` its labels are on, e.g.
+    ///
+    /// ```text
+    /// error[T004]: expected rank 1, found 2
+    ///  --> file.tqo:1:7
+    ///   |
+    /// 1 | 1_2 + [1_2 ⋄ 3_4]
+    ///   |       ^^^^^^^^^^^ rank 2
+    /// ```
+    pub fn render(&self, src: &str, file: Option<&str>) -> String {
+        let mut out = String::new();
+        let _ = match self.code {
+            Some(code) => writeln!(out, "{}[{code}]: {}", self.severity.name(), self.message),
+            None => writeln!(out, "{}: {}", self.severity.name(), self.message),
+        };
+        let lines = line_starts(src);
+        let mut rows = vec![];
+        for label in &self.labels {
+            let (first, last) = (line_of(&lines, label.span.start), line_of(&lines, label.span.end.max(label.span.start + 1) - 1));
+            for line in first..=last.max(first) {
+                rows.push((line, label));
+            }
+        }
+        let gutter = rows.iter().map(|(line, _)| (line + 1).to_string().len()).max().unwrap_or(1);
+        let pad = " ".repeat(gutter);
+        if let Some(primary) = self.labels.first() {
+            let line = line_of(&lines, primary.span.start);
+            let column = width(&src[lines[line]..primary.span.start]) + 1;
+            let _ = writeln!(out, "{pad}--> {}:{}:{column}", file.unwrap_or("<input>"), line + 1);
+            let _ = writeln!(out, "{pad} |");
+        }
+        rows.sort_by_key(|(line, label)| (*line, !label.primary));
+        let mut shown = None;
+        for (line, label) in rows {
+            let (start, end) = (lines[line], line_end(src, &lines, line));
+            if shown != Some(line) {
+                let _ = writeln!(out, "{:>gutter$} | {}", line + 1, expand(&src[start..end]));
+                shown = Some(line);
+            }
+            let (from, to) = (label.span.start.clamp(start, end), label.span.end.clamp(start, end));
+            let before = width(&src[start..from]);
+            let marker = if label.primary { "^" } else { "-" };
+            let underline = marker.repeat(width(&src[from..to]).max(1));
+            // The message goes under the span's last line
+            let message = match label.span.end <= end || line + 1 == lines.len() {
+                true if !label.message.is_empty() => format!(" {}", label.message),
+                _ => String::new(),
+            };
+            let _ = writeln!(out, "{pad} | {}{underline}{message}", " ".repeat(before));
+        }
+        if !self.notes.is_empty() || self.help.is_some() {
+            let _ = writeln!(out, "{pad} |");
+        }
+        for note in &self.notes {
+            let _ = writeln!(out, "{pad} = note: {note}");
+        }
+        if let Some(help) = &self.help {
+            let _ = writeln!(out, "{pad} = help: {help}");
+        }
+        out
+    }
+
+    /// The diagnostic as a single line of JSON, for editors and the like. Each label has its span
+    /// both in bytes and as 1-based lines and columns, with columns counted in characters, e.g.
+    ///
+    /// ```text
+    /// {"file":"a.tqo","severity":"error","code":"T004","message":"expected rank 1, found 2",
+    ///  "labels":[{"primary":true,"message":"","start":6,"end":17,"line":1,"column":7,
+    ///  "end_line":1,"end_column":18}],"notes":[],"help":null}
+    /// ```
+    pub fn json(&self, src: &str, file: Option<&str>) -> String {
+        let lines = line_starts(src);
+        let position = |offset: usize| {
+            let line = line_of(&lines, offset);
+            (line + 1, src[lines[line]..offset].chars().count() + 1)
+        };
+        let labels = self.labels.iter().map(|label| {
+            let (line, column) = position(label.span.start);
+            let (end_line, end_column) = position(label.span.end);
+            format!(
+                r#"{{"primary":{},"message":{},"start":{},"end":{},"line":{line},"column":{column},"end_line":{end_line},"end_column":{end_column}}}"#,
+                label.primary,
+                quoted(&label.message),
+                label.span.start,
+                label.span.end,
+            )
+        });
+        let notes = self.notes.iter().map(|note| quoted(note));
+        format!(
+            r#"{{"file":{},"severity":{},"code":{},"message":{},"labels":[{}],"notes":[{}],"help":{}}}"#,
+            file.map_or("null".into(), quoted),
+            quoted(self.severity.name()),
+            self.code.map_or("null".into(), quoted),
+            quoted(&self.message),
+            labels.collect::<Vec<_>>().join(","),
+            notes.collect::<Vec<_>>().join(","),
+            self.help.as_deref().map_or("null".into(), quoted),
+        )
+    }
+}
+
+impl Severity {
+    fn name(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+}
+
+/// `s` as a JSON string
+fn quoted(s: &str) -> String {
+    let mut out = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// The byte offset each line starts at
+fn line_starts(src: &str) -> Vec<usize> {
+    std::iter::once(0).chain(src.match_indices('\n').map(|(i, _)| i + 1)).collect()
+}
+fn line_of(lines: &[usize], offset: usize) -> usize {
+    lines.partition_point(|&start| start <= offset).saturating_sub(1)
+}
+/// Where `line` ends, not counting its newline
+fn line_end(src: &str, lines: &[usize], line: usize) -> usize {
+    lines.get(line + 1).map_or(src.len(), |next| next - 1)
+}
+
+/// Tabs are shown as four spaces, so that carets line up with what's above them
+const TAB: &str = "    ";
+fn expand(line: &str) -> String {
+    line.replace('\t', TAB)
+}
+
+/// How many columns `s` takes up in a terminal
+pub(crate) fn width(s: &str) -> usize {
+    s.chars().map(char_width).sum()
+}
+fn char_width(c: char) -> usize {
+    match c as u32 {
+        0x09 => TAB.len(),
+        // Combining marks and zero-width spaces and joiners
+        0x0300..=0x036F | 0x200B..=0x200F | 0x20D0..=0x20FF | 0xFE00..=0xFE0F => 0,
+        // East Asian wide and fullwidth characters, and most emoji
+        0x1100..=0x115F
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1F64F
+        | 0x1F900..=0x1F9FF
+        | 0x20000..=0x3FFFD => 2,
+        _ => 1,
+    }
+}
+
+impl<'src> TError<'src> {
+    /// What went wrong, labelled with where and with what might help
+    pub fn diagnostic(&self) -> Diagnostic<'src> {
+        let (label, help) = match &self.kind {
+            TErrorKind::Parse(kind) => ("here", parse_help(*kind)),
+            TErrorKind::Type(kind) => ("", type_help(kind)),
+            TErrorKind::UnknownName => ("not bound yet", None),
+            TErrorKind::RepeatLimit(_) => ("", Some("check that the condition can ever hold")),
+            _ => ("", None),
+        };
+        let mut d = Diagnostic::error(self.kind.to_string(), self.span, label).with_code(self.kind.code());
+        for (span, message) in &self.related {
+            d = d.with_label(*span, message.clone());
+        }
+        if let TErrorKind::Type(TypeCheckErrKind::Conflict { .. }) = self.kind {
+            d = d.with_note("a type variable stands for the same thing everywhere in a call");
+        }
+        match help {
+            Some(help) => d.with_help(help),
+            None => d,
+        }
+    }
+}
+
+fn parse_help(kind: TParseErrKind) -> Option<&'static str> {
+    use TParseErrKind as K;
+    match kind {
+        K::CannotBind => Some("arrays are joined with `_`, as in `1_2_3`, and trains go in `⦅⦆`"),
+        K::DfnNoArguments => Some("use α or ω, or bind the value to a name instead"),
+        K::DfnTooDeep => Some("name an inner dfn and refer to it instead"),
+        K::BadTrain => Some("a train is `⦅f g⦆` with g monadic, or `⦅f g h⦆` with g dyadic"),
+        K::BadSignature => Some("signatures look like `: ∀T 'T » Name « 'T ↳ 'T`"),
+        _ => None,
+    }
+}
+
+fn type_help(kind: &TypeCheckErrKind) -> Option<&'static str> {
+    use TypeCheckErrKind as K;
+    match kind {
+        K::NoSignature { inferred: Some(_), .. } => Some("paste the signature above the binding"),
+        K::NoSignature { inferred: None, .. } => Some("write one above the binding, as in `: 'Nat » F ↳ 'Nat`"),
+        K::NonExhaustive(_) => Some("add arms for them, or one that matches anything with `_`"),
+        K::Unbound(_) => Some("put the binding right after its signature"),
+        K::BadRelative => Some("write `⍤₁` for ⍺'s rank or `⍤₂` for ω's"),
+        _ => None,
+    }
+}
+
+#[test]
+fn rendering() {
+    let src = "x ← 1\n'α' + ⍳[1 ⋄ 2]";
+    let (start, char) = (src.find('⍳').unwrap(), src.find('\'').unwrap());
+    let d = Diagnostic::error("expected a number", Sp::new(start, src.len()), "this")
+        .with_label(Sp::new(char, char + "'α'".len()), "not a number")
+        .with_help("don't");
+    let expected = "error: expected a number
+ --> t.tqo:2:7
+  |
+2 | 'α' + ⍳[1 ⋄ 2]
+  |       ^^^^^^^^ this
+  | --- not a number
+  |
+  = help: don't
+";
+    assert_eq!(d.render(src, Some("t.tqo")), expected);
+    // Wide characters take up two columns
+    let src = "漢字 + 1";
+    let d = Diagnostic::error("no", Sp::new(7, 8), "");
+    assert!(d.render(src, None).contains("\n  |      ^\n"));
+}
+
+#[test]
+fn json() {
+    let src = "x ← 1\n\"é\" ¤";
+    let at = src.find('¤').unwrap();
+    let d = Diagnostic::error("unknown glyph", Sp::new(at, at + '¤'.len_utf8()), "here")
+        .with_code("P003")
+        .with_note("tabs\tand \"quotes\"");
+    let expected = format!(
+        r#"{{"file":"a.tqo","severity":"error","code":"P003","message":"unknown glyph","labels":[{{"primary":true,"message":"here","start":{at},"end":{},"line":2,"column":5,"end_line":2,"end_column":6}}],"notes":["tabs\tand \"quotes\""],"help":null}}"#,
+        at + 2
+    );
+    assert_eq!(d.json(src, Some("a.tqo")), expected);
+}
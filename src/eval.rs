@@ -0,0 +1,1049 @@
+//! Tree-walking evaluation of parsed expressions
+
+use std::rc::Rc;
+
+use crate::{
+    glyphs::respell,
+    primitive::{frame_and_cells, stack},
+    *,
+};
+
+#[derive(Clone, Default)]
+pub struct Evaluator {
+    env: TEnv,
+    types: TypeEnv,
+    /// Each binding and declaration run so far that's still needed to get back to the current
+    /// names, oldest first
+    definitions: Vec<Definition>,
+    /// How many times [`Evaluator::run`] or the like has been called, which tells apart the
+    /// definitions made by each
+    runs: u32,
+    /// The source of the latest signature for each name, which goes along with its definition
+    signature_sources: HashMap<String, String>,
+    /// The locals of each dfn being called, innermost last
+    frames: Vec<HashMap<Ident, TNoun>>,
+    /// What the code being run was written in, innermost call last, which its spans refer to
+    sources: Vec<Rc<str>>,
+    /// How many times a `⍣` without a count may iterate before giving up. Unbounded if `None`
+    repeat_limit: Option<usize>,
+}
+
+/// A statement that bound names, as kept for [`Evaluator::workspace`]
+#[derive(Clone)]
+struct Definition {
+    bound: Vec<String>,
+    /// Those of `bound` that haven't been bound again or erased since
+    live: Vec<String>,
+    /// Along with the signature of what it binds, if any
+    src: String,
+    /// Which of [`Evaluator::runs`] it was made in
+    run: u32,
+}
+
+impl Definition {
+    /// Whether it reads `name`, not counting the names it binds
+    fn reads(&self, name: &str) -> bool {
+        let Ok(statements) = lex(&self.src) else { return false };
+        statements.iter().any(|st| match &st[..] {
+            [_, ExprToken { kind: ExprTokenKind::Arrow, .. }, rest @ ..] => uses(&self.src, rest, name),
+            st => uses(&self.src, st, name),
+        })
+    }
+}
+
+impl Evaluator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Guards against `⍣`s whose condition never holds
+    pub fn with_repeat_limit(mut self, limit: usize) -> Self {
+        self.repeat_limit = Some(limit);
+        self
+    }
+    pub fn repeat_limit(&self) -> Option<usize> {
+        self.repeat_limit
+    }
+    /// Rejects functions bound without a `:` signature
+    pub fn with_signatures_required(mut self) -> Self {
+        self.types.require_signatures();
+        self
+    }
+    /// Makes `args` available to the program as `⎕args`, a matrix of chars with one row for each
+    /// argument, padded with spaces to the length of the longest one
+    pub fn with_args(mut self, args: &[String]) -> Self {
+        let width = args.iter().map(|a| a.chars().count()).max().unwrap_or(0);
+        let data = args
+            .iter()
+            .flat_map(|a| a.chars().chain(std::iter::repeat(' ')).take(width))
+            .map(|c| TNoun::Atom(TAtom::Char(TChar(c))))
+            .collect();
+        let shape = vec![args.len() as u32, width as u32];
+        self.types.predefine("⎕args", &Known::new(Some(TAtomKind::Char), Some(shape.clone())));
+        self.env.0.insert(Ident("⎕args".to_string()), TArray::noun(shape, data));
+        self
+    }
+    /// The signature `name` has, or could be given if it has none
+    pub fn signature_of(&self, name: &str) -> Option<String> {
+        self.types.signature_of(name)
+    }
+
+    /// Type checks and runs every statement in `src`, giving back the value of the last one
+    pub fn run<'src>(&mut self, src: &'src str) -> TResult<'src, Option<TNoun>> {
+        Ok(self.execute(src)?.map(|(val, _, _)| val))
+    }
+    /// Like [`Evaluator::run`], but also gives back the type of the value, written as it would be in
+    /// a signature. Gives back nothing if the last statement binds a name, as there's no need to
+    /// show what was just written
+    pub fn run_shown<'src>(&mut self, src: &'src str) -> TResult<'src, Option<(TNoun, String)>> {
+        Ok(match self.execute(src)? {
+            Some((val, t, false)) => Some((val, self.types.described(&t))),
+            _ => None,
+        })
+    }
+    /// The type `src` would have if it were run, without binding anything
+    pub fn type_of<'src>(&mut self, src: &'src str) -> TResult<'src, Option<String>> {
+        let types = self.types.clone();
+        let t = self.check(src);
+        let described = t.map(|t| t.map(|t| self.types.described(&t)));
+        self.types = types;
+        described
+    }
+    /// The names bound to arrays, sorted
+    pub fn vars(&self) -> Vec<String> {
+        self.types.bound(|cat| cat == Category::A)
+    }
+    /// The names bound to functions, sorted
+    pub fn fns(&self) -> Vec<String> {
+        self.types.bound(|cat| cat != Category::A)
+    }
+    /// Unbinds `name`, giving back whether it was bound
+    pub fn erase(&mut self, name: &str) -> bool {
+        let ident = Ident(name.to_string());
+        let bound = self.types.erase(&ident);
+        self.env.0.remove(&ident);
+        self.signature_sources.remove(name);
+        self.supersede(&[name.to_string()]);
+        bound
+    }
+    /// The source of the statement that bound `name`, along with its signature
+    pub fn definition(&self, name: &str) -> Option<&str> {
+        let d = self.definitions.iter().find(|d| d.live.iter().any(|n| n == name))?;
+        Some(&d.src)
+    }
+    /// The source of every binding and declaration that's still in effect, in the order they were
+    /// run. Running it in a new evaluator gets back to the same names
+    pub fn workspace(&self) -> String {
+        self.definitions.iter().map(|d| format!("{}\n", d.src)).collect()
+    }
+    /// Forgets that earlier definitions bound `names`. Those that no longer bind anything are
+    /// dropped, unless a later one reads what they bound
+    fn supersede(&mut self, names: &[String]) {
+        for d in &mut self.definitions {
+            d.live.retain(|name| !names.contains(name));
+        }
+        let mut kept: Vec<Definition> = vec![];
+        for d in std::mem::take(&mut self.definitions).into_iter().rev() {
+            if !d.live.is_empty() || kept.iter().any(|later| d.bound.iter().any(|name| later.reads(name))) {
+                kept.push(d);
+            }
+        }
+        kept.reverse();
+        self.definitions = kept;
+    }
+    /// Records `src` as what binds `names`
+    fn remember(&mut self, names: Vec<String>, src: String) {
+        self.supersede(&names);
+        self.definitions.push(Definition {
+            bound: names.clone(),
+            live: names,
+            src,
+            run: self.runs,
+        });
+    }
+    /// Runs `src` as a new version of definitions made before, keeping what it binds only if it all
+    /// runs. Gives back the source of the other bindings that read what it binds, which were checked
+    /// against the old definitions and may no longer type check, see [`Evaluator::type_of`]
+    pub fn reload<'src>(&mut self, src: &'src str) -> TResult<'src, Vec<String>> {
+        let mut reloaded = self.clone();
+        reloaded.run(src)?;
+        *self = reloaded;
+        let rebound = (self.definitions.iter())
+            .filter(|d| d.run == self.runs)
+            .flat_map(|d| d.bound.iter())
+            .collect::<Vec<_>>();
+        let dependents = self.definitions.iter().filter(|d| {
+            d.run != self.runs && !d.live.is_empty() && rebound.iter().any(|name| d.reads(name))
+        });
+        Ok(dependents.map(|d| d.src.clone()).collect())
+    }
+    /// Whether `src` has brackets left open, so that more of it is to come
+    pub fn incomplete(src: &str) -> bool {
+        matches!(lex(&respell(src).text), Err(e) if e.kind == TParseErrKind::UnclosedDelimiter)
+    }
+    /// The value of the last statement with its type and whether it's a binding
+    fn execute<'src>(&mut self, src: &'src str) -> TResult<'src, Option<(TNoun, TType, bool)>> {
+        let respelled = respell(src);
+        self.execute_glyphs(&respelled.text).map_err(|e| e.moved(|pos| respelled.original(pos)))
+    }
+    /// [`Evaluator::execute`], once glyphs spelled out by name are turned into glyphs
+    fn execute_glyphs<'src>(&mut self, src: &'src str) -> TResult<'src, Option<(TNoun, TType, bool)>> {
+        let shared = Rc::from(src);
+        self.sources = vec![Rc::clone(&shared)];
+        self.runs += 1;
+        let mut last = None;
+        let mut signed = None;
+        for statement in lex(src)? {
+            let text = match (statement.first(), statement.last()) {
+                (Some(first), Some(last)) => &src[first.span.start..last.span.end],
+                _ => "",
+            };
+            if let Some(signature) = self.sign(&statement)? {
+                self.signature_sources.insert(signature.0.clone(), text.to_string());
+                signed = Some(signature);
+                continue;
+            }
+            if let Some(decl) = self.declare(&statement)? {
+                self.define(&decl);
+                let mut names = vec![decl.name.clone()];
+                names.extend((0..decl.variants.len()).map(|v| decl.constructor(v).to_string()));
+                self.remember(names, text.to_string());
+                continue;
+            }
+            let tree = self.parse(src, statement)?;
+            paired(signed.take(), &tree)?;
+            let t = typecheck_expr(src, &tree, &mut self.types)?;
+            let names = match &tree {
+                ExprTree::Assignment { name, .. } => Some(vec![name.clone()]),
+                ExprTree::Destructure { names, .. } => Some(names.clone()),
+                _ => None,
+            };
+            last = Some((self.eval(&shared, &tree)?, t, names.is_some()));
+            if let Some(names) = names {
+                let text = match &names[..] {
+                    [name] if self.signature_sources.contains_key(name) => {
+                        format!("{}\n{text}", self.signature_sources[name])
+                    }
+                    _ => text.to_string(),
+                };
+                self.remember(names, text);
+            }
+        }
+        Ok(last)
+    }
+    /// Type checks every statement in `src` without running it, giving back the type of the last one
+    pub fn check<'src>(&mut self, src: &'src str) -> TResult<'src, Option<TType>> {
+        let respelled = respell(src);
+        self.check_glyphs(&respelled.text).map_err(|e| e.moved(|pos| respelled.original(pos)))
+    }
+    /// [`Evaluator::check`], once glyphs spelled out by name are turned into glyphs
+    fn check_glyphs<'src>(&mut self, src: &'src str) -> TResult<'src, Option<TType>> {
+        let mut last = None;
+        let mut signed = None;
+        for statement in lex(src)? {
+            last = self.check_statement(src, statement, &mut signed)?.or(last);
+        }
+        Ok(last)
+    }
+    /// Like [`Evaluator::check`], but carries on past errors so as to report all of them. A binding
+    /// that fails still binds its name, to the type of its signature or to what little is known of
+    /// it, so that its uses don't fail as well. If not even its category is known, they're skipped
+    pub fn check_all<'src>(&mut self, src: &'src str) -> Result<Option<TType>, Vec<TError<'src>>> {
+        let respelled = respell(src);
+        let moved = |errors: Vec<TError>| errors.into_iter().map(|e| e.moved(|pos| respelled.original(pos))).collect();
+        self.check_all_glyphs(&respelled.text).map_err(moved)
+    }
+    /// [`Evaluator::check_all`], once glyphs spelled out by name are turned into glyphs
+    fn check_all_glyphs<'src>(&mut self, src: &'src str) -> Result<Option<TType>, Vec<TError<'src>>> {
+        let (statements, lex_errors) = lex_recovering(src);
+        let mut errors = vec![];
+        // The names that couldn't be bound, and where from. Statements using them can't be parsed,
+        // so they're skipped rather than reporting the same error again
+        let mut unbound = vec![];
+        for (err, partial) in lex_errors {
+            unbound.extend(self.placeholder(src, &partial).map(|name| (name, err.span.start)));
+            errors.push(err.into());
+        }
+        let mut last = None;
+        let mut signed = None;
+        for statement in statements {
+            let start = statement[0].span.start;
+            let target = assigned(src, &statement);
+            let value = &statement[target.map_or(0, |_| 2)..];
+            if unbound.iter().any(|&(name, from)| from <= start && uses(src, value, name)) {
+                unbound.extend(target.map(|name| (name, start)));
+                continue;
+            }
+            unbound.retain(|&(name, from)| from > start || Some(name) != target);
+            let failed = statement.clone();
+            match self.check_statement(src, statement, &mut signed) {
+                Ok(t) => last = t.or(last),
+                Err(err) => {
+                    unbound.extend(self.placeholder(src, &failed).map(|name| (name, start)));
+                    signed = None;
+                    errors.push(err);
+                }
+            }
+        }
+        errors.sort_by_key(|e| e.span.start);
+        match errors.is_empty() {
+            true => Ok(last),
+            false => Err(errors),
+        }
+    }
+    /// Binds the name `statement` assigns to, if any, as it failed to be. Gives it back if what it
+    /// is can't be told either
+    fn placeholder<'src>(&mut self, src: &'src str, statement: &[ExprToken<'src>]) -> Option<&'src str> {
+        let name = assigned(src, statement)?;
+        let cat = match &statement[2..] {
+            [ExprToken { kind: ExprTokenKind::Dfn { cat, .. }, .. }] => Some(*cat),
+            _ => self.parse(src, statement.to_vec()).ok().map(|tree| tree.category()),
+        };
+        (!self.types.placeholder(name, cat)).then_some(name)
+    }
+    /// The type of `statement`, if it's not a signature or a declaration
+    fn check_statement<'src>(
+        &mut self,
+        src: &'src str,
+        statement: Vec<ExprToken<'src>>,
+        signed: &mut Option<(String, Sp<'src>)>,
+    ) -> TResult<'src, Option<TType>> {
+        if let Some(signature) = self.sign(&statement)? {
+            *signed = Some(signature);
+            return Ok(None);
+        }
+        if self.declare(&statement)?.is_some() {
+            return Ok(None);
+        }
+        let tree = self.parse(src, statement)?;
+        paired(signed.take(), &tree)?;
+        typecheck_expr(src, &tree, &mut self.types).map(Some).map_err(TError::from)
+    }
+    /// Registers the types in `statement` if it's a `□` declaration
+    fn declare<'src>(&mut self, statement: &[ExprToken<'src>]) -> TResult<'src, Option<Rc<DataDecl>>> {
+        match statement {
+            [ExprToken {
+                kind: ExprTokenKind::Data(syntax),
+                span,
+            }] => match self.types.declare(syntax) {
+                Ok(decl) => Ok(Some(decl)),
+                Err(kind) => Err(TypeCheckErr { span: *span, kind }.into()),
+            },
+            _ => Ok(None),
+        }
+    }
+    /// Registers the type in `statement` if it's a `:` signature, giving back the name it's for
+    fn sign<'src>(&mut self, statement: &[ExprToken<'src>]) -> TResult<'src, Option<(String, Sp<'src>)>> {
+        match statement {
+            [ExprToken {
+                kind: ExprTokenKind::Signature(syntax),
+                span,
+            }] => match self.types.sign(syntax) {
+                Ok(()) => Ok(Some((syntax.name.clone(), *span))),
+                Err(kind) => Err(TypeCheckErr { span: *span, kind }.into()),
+            },
+            _ => Ok(None),
+        }
+    }
+    /// Binds the constructors of `decl`. Variants without fields are values rather than functions
+    fn define(&mut self, decl: &Rc<DataDecl>) {
+        for (variant, v) in decl.variants.iter().enumerate() {
+            let val = match v.fields.len() {
+                0 => TNoun::Atom(TAtom::SumType(TSumType {
+                    decl: decl.clone(),
+                    variant,
+                    fields: vec![],
+                })),
+                _ => TNoun::function(TFunction::Constructor { decl: decl.clone(), variant }, Category::Ov),
+            };
+            self.env.0.insert(Ident(decl.constructor(variant).to_string()), val);
+        }
+    }
+    /// What names refer to depends on the previous statements, so each is parsed just before it's used
+    fn parse<'src>(&self, src: &'src str, mut statement: Vec<ExprToken<'src>>) -> TResult<'src, ExprTree<'src>> {
+        resolve_names(src, &mut statement, &|name| self.types.category_of(name));
+        parse_expr(src, &statement, statement[0].span)
+    }
+
+    pub(crate) fn eval<'src>(&mut self, src: &Rc<str>, e: &ExprTree<'src>) -> TResult<'src, TNoun> {
+        let span = e.span();
+        match e {
+            ExprTree::Leaf { t, .. } => self.eval_token(src, t),
+            ExprTree::AlphaVerbCall { alpha, verb } => {
+                let alpha = self.eval(src, alpha)?;
+                self.eval(src, verb)?.call(self, span, Some(alpha), None)
+            }
+            ExprTree::OmegaVerbCall { verb, omega } => {
+                let verb = self.eval(src, verb)?;
+                let omega = self.eval(src, omega)?;
+                verb.call(self, span, None, Some(omega))
+            }
+            ExprTree::DyadicVerbCall { verb, alpha, omega } => {
+                let alpha = self.eval(src, alpha)?;
+                let verb = self.eval(src, verb)?;
+                let omega = self.eval(src, omega)?;
+                verb.call(self, span, Some(alpha), Some(omega))
+            }
+            ExprTree::AlphaBound { alpha, verb } => {
+                let alpha = Box::new(self.eval(src, alpha)?);
+                let f = Box::new(self.eval_function(src, verb)?);
+                Ok(TNoun::function(TFunction::AlphaBound { alpha, f }, Category::Ov))
+            }
+            ExprTree::OmegaBound { verb, omega } => {
+                let f = Box::new(self.eval_function(src, verb)?);
+                let omega = Box::new(self.eval(src, omega)?);
+                Ok(TNoun::function(TFunction::OmegaBound { f, omega }, Category::Av))
+            }
+            ExprTree::Assignment { name, val } => {
+                let val = self.eval(src, val)?;
+                self.assign(name, val.clone());
+                Ok(val)
+            }
+            ExprTree::Destructure { names, val, .. } => {
+                let val = self.eval(src, val)?;
+                let (_, cells) = frame_and_cells(val.clone());
+                if cells.len() != names.len() {
+                    return Err(TError::new(TErrorKind::Length, span));
+                }
+                for (name, cell) in names.iter().zip(cells) {
+                    self.assign(name, cell);
+                }
+                Ok(val)
+            }
+            ExprTree::AlphaAdverbCall { adverb, alpha, cat } => {
+                let alpha = Some(Box::new(self.eval(src, alpha)?));
+                let modifier = modifier_of(src, adverb);
+                Ok(TNoun::function(TFunction::Derived { modifier, alpha, omega: None }, *cat))
+            }
+            ExprTree::OmegaAdverbCall { adverb, omega, cat } => {
+                let omega = Some(Box::new(self.eval(src, omega)?));
+                let modifier = modifier_of(src, adverb);
+                Ok(TNoun::function(TFunction::Derived { modifier, alpha: None, omega }, *cat))
+            }
+            ExprTree::DyadicAdverbCall {
+                adverb,
+                alpha,
+                omega,
+                cat,
+            } => {
+                let alpha = Some(Box::new(self.eval(src, alpha)?));
+                let omega = Some(Box::new(self.eval(src, omega)?));
+                let modifier = modifier_of(src, adverb);
+                Ok(TNoun::function(TFunction::Derived { modifier, alpha, omega }, *cat))
+            }
+            ExprTree::AlphaOperand { .. } | ExprTree::OmegaOperand { .. } => {
+                unreachable!("parser rejects incomplete expressions")
+            }
+            // Globals are looked up when called, so that dfns can refer to themselves
+            ExprTree::Dfn { body, cat, depth, .. } => {
+                let dfn = Dfn {
+                    body: body.iter().map(ExprTree::to_static).collect(),
+                    depth: *depth,
+                    captured: self.frames.last().cloned().unwrap_or_default(),
+                    src: src.clone(),
+                };
+                Ok(TNoun::function(TFunction::Dfn(Rc::new(dfn)), *cat))
+            }
+            ExprTree::Bracket { items, .. } => {
+                let items = items.iter().map(|t| self.eval(src, t)).collect::<Result<_, _>>()?;
+                stack(items).map_err(|kind| TError::new(kind, span))
+            }
+            ExprTree::Strand { items, .. } => {
+                let items = items.iter().map(|t| self.eval(src, t)).collect::<Result<_, _>>()?;
+                Ok(TArray::vector(items))
+            }
+            ExprTree::Match { arms, .. } => {
+                let m = Match {
+                    arms: arms.iter().map(|(p, body)| (p.to_static(), body.to_static())).collect(),
+                    captured: self.frames.last().cloned().unwrap_or_default(),
+                    src: src.clone(),
+                };
+                Ok(TNoun::function(TFunction::Match(Rc::new(m)), Category::Av))
+            }
+            ExprTree::Train { tines, cat, .. } => {
+                let tines = tines.iter().map(|t| self.eval(src, t)).collect::<Result<_, _>>()?;
+                Ok(TNoun::function(TFunction::Train(tines), *cat))
+            }
+        }
+    }
+
+    pub(crate) fn call_dfn<'src>(
+        &mut self,
+        dfn: &Dfn,
+        span: Sp<'src>,
+        alpha: Option<TNoun>,
+        omega: Option<TNoun>,
+    ) -> TResult<'src, TNoun> {
+        let mut locals = dfn.captured.clone();
+        let (a, w) = ARGUMENTS[dfn.depth - 1];
+        for (name, arg) in [(a, alpha), (w, omega)] {
+            if let Some(arg) = arg {
+                locals.insert(Ident(name.to_string()), arg);
+            }
+        }
+        self.frames.push(locals);
+        let result = self.inside(&dfn.src, span, |ev| {
+            dfn.body.iter().try_fold(None, |_, e| ev.eval(&dfn.src, e).map(Some))
+        });
+        self.frames.pop();
+        Ok(result?.expect("dfns have at least one statement"))
+    }
+    /// Runs `f` on code written in `src`, called from `span`. Its errors are put down to the call if
+    /// it was written somewhere else than the caller, e.g. in an earlier line of the repl, as their
+    /// spans would point at the wrong thing
+    fn inside<'src, T>(
+        &mut self,
+        src: &Rc<str>,
+        span: Sp<'src>,
+        f: impl FnOnce(&mut Self) -> TResult<'src, T>,
+    ) -> TResult<'src, T> {
+        let elsewhere = self.sources.last().is_some_and(|caller| !Rc::ptr_eq(caller, src));
+        self.sources.push(Rc::clone(src));
+        let result = f(self);
+        self.sources.pop();
+        match result {
+            Err(e) if elsewhere => Err(TError::new(e.kind, span)),
+            result => result,
+        }
+    }
+
+    /// The result of the first arm whose pattern `x` fits, with what the pattern binds
+    pub(crate) fn call_match<'src>(&mut self, m: &Match, span: Sp<'src>, x: TNoun) -> TResult<'src, TNoun> {
+        for (pattern, body) in &m.arms {
+            let mut locals = m.captured.clone();
+            if self.inside(&m.src, span, |ev| ev.matches(&m.src, pattern, &x, &mut locals))? {
+                self.frames.push(locals);
+                let result = self.inside(&m.src, span, |ev| ev.eval(&m.src, body));
+                self.frames.pop();
+                return result;
+            }
+        }
+        Err(TError::new(TErrorKind::NoMatch, span))
+    }
+
+    /// Whether `x` fits `pattern`, adding what it binds to `locals`
+    fn matches(
+        &mut self,
+        src: &str,
+        pattern: &Pattern<'static>,
+        x: &TNoun,
+        locals: &mut HashMap<Ident, TNoun>,
+    ) -> TResult<'static, bool> {
+        let value = match x {
+            TNoun::Atom(TAtom::SumType(v)) => Some(v),
+            _ => None,
+        };
+        match pattern {
+            Pattern::Wildcard => Ok(true),
+            Pattern::Name(name) => match value {
+                Some(v) if v.decl.variant(name).is_some() => Ok(v.decl.constructor(v.variant) == name),
+                _ => {
+                    locals.insert(Ident(name.clone()), x.clone());
+                    Ok(true)
+                }
+            },
+            Pattern::Literal(t) => Ok(self.eval_token(src, t)? == *x),
+            Pattern::Variant { name, fields } => match value {
+                Some(v) if v.decl.constructor(v.variant) == name => {
+                    if fields.is_empty() {
+                        return Ok(true);
+                    }
+                    if fields.len() != v.fields.len() {
+                        return Ok(false);
+                    }
+                    for (p, field) in fields.iter().zip(&v.fields) {
+                        if !self.matches(src, p, field, locals)? {
+                            return Ok(false);
+                        }
+                    }
+                    Ok(true)
+                }
+                _ => Ok(false),
+            },
+            Pattern::Array(items) => match x {
+                TNoun::Array(arr) if arr.shape[0] as usize == items.len() => {
+                    let (_, cells) = frame_and_cells(x.clone());
+                    for (p, cell) in items.iter().zip(&cells) {
+                        if !self.matches(src, p, cell, locals)? {
+                            return Ok(false);
+                        }
+                    }
+                    Ok(true)
+                }
+                _ => Ok(false),
+            },
+        }
+    }
+
+    fn eval_function<'src>(&mut self, src: &Rc<str>, e: &ExprTree<'src>) -> TResult<'src, TFunction> {
+        let f = self.eval(src, e)?;
+        match f.as_function() {
+            Some((f, _)) => Ok(f.clone()),
+            None => Err(TError::new(TErrorKind::Domain, e.span())),
+        }
+    }
+
+    /// Into the innermost dfn's locals, or the globals outside of dfns
+    fn assign(&mut self, name: &str, val: TNoun) {
+        match self.frames.last_mut() {
+            Some(locals) => locals.insert(Ident(name.to_string()), val),
+            None => self.env.0.insert(Ident(name.to_string()), val),
+        };
+    }
+
+    fn eval_token<'src>(&mut self, src: &str, t: &ExprToken<'src>) -> TResult<'src, TNoun> {
+        let text = t.span.slice(src);
+        let prim = || {
+            let glyph = text.chars().next().expect("tokens are non-empty");
+            TFunction::Primitive(Primitive::from_glyph(glyph).expect("lexer only emits known primitives"))
+        };
+        match t.kind {
+            ExprTokenKind::Number => text
+                .parse()
+                .map(TNoun::nat)
+                .map_err(|_| TError::new(TErrorKind::Overflow, t.span)),
+            ExprTokenKind::String => Ok(TArray::vector(
+                unescape(&text[1..text.len() - 1])
+                    .chars()
+                    .map(|c| TNoun::Atom(TAtom::Char(TChar(c))))
+                    .collect(),
+            )),
+            ExprTokenKind::Char => {
+                let c = unescape(&text[1..text.len() - 1]).chars().next();
+                Ok(TNoun::Atom(TAtom::Char(TChar(c.expect("lexer checks char literals")))))
+            }
+            ExprTokenKind::PrimAVerb => Ok(TNoun::function(prim(), Category::Av)),
+            ExprTokenKind::PrimOVerb => Ok(TNoun::function(prim(), Category::Ov)),
+            ExprTokenKind::PrimDVerb => Ok(TNoun::function(prim(), Category::Dv)),
+            ExprTokenKind::Field => Ok(TNoun::function(TFunction::Field(text[1..].to_string()), Category::Av)),
+            ExprTokenKind::ArrayName
+            | ExprTokenKind::AVerbName
+            | ExprTokenKind::OVerbName
+            | ExprTokenKind::DVerbName => {
+                let name = Ident(text.to_string());
+                self.frames
+                    .last()
+                    .and_then(|locals| locals.get(&name))
+                    .or_else(|| self.env.0.get(&name))
+                    .cloned()
+                    .ok_or(TError::new(TErrorKind::UnknownName, t.span))
+            }
+            ExprTokenKind::PrimArray
+            | ExprTokenKind::PrimAAdverb
+            | ExprTokenKind::PrimOAdverb
+            | ExprTokenKind::PrimDAdverb
+            | ExprTokenKind::OAdverbName
+            | ExprTokenKind::DAdverbName
+            | ExprTokenKind::Jot
+            | ExprTokenKind::Arrow
+            | ExprTokenKind::Parenthesized(_)
+            | ExprTokenKind::Train(_)
+            | ExprTokenKind::Strand(_)
+            | ExprTokenKind::Bracket(_)
+            | ExprTokenKind::Data(_)
+            | ExprTokenKind::Signature(_)
+            | ExprTokenKind::Match(_)
+            | ExprTokenKind::Then
+            | ExprTokenKind::Wildcard
+            | ExprTokenKind::Dfn { .. } => unreachable!("{:?} is not a value", t.kind),
+        }
+    }
+}
+
+/// Only primitive modifiers exist for now
+fn modifier_of(src: &str, adverb: &ExprTree) -> Primitive {
+    let glyph = adverb.span().slice(src).chars().next().expect("tokens are non-empty");
+    Primitive::from_glyph(glyph).expect("lexer only emits known primitives")
+}
+
+/// The name `statement` binds, if it's a binding to a name
+fn assigned<'src>(src: &'src str, statement: &[ExprToken<'src>]) -> Option<&'src str> {
+    match statement {
+        [name @ ExprToken { kind: ExprTokenKind::ArrayName, .. }, ExprToken { kind: ExprTokenKind::Arrow, .. }, ..] => {
+            Some(name.span.slice(src))
+        }
+        _ => None,
+    }
+}
+
+/// A signature must be followed by the binding it describes
+fn paired<'src>(signed: Option<(String, Sp<'src>)>, tree: &ExprTree<'src>) -> TResult<'src, ()> {
+    match (signed, tree) {
+        (Some((name, _)), ExprTree::Assignment { name: bound, .. }) if name == *bound => Ok(()),
+        (Some((name, span)), _) => {
+            let err: TError = TypeCheckErr {
+                span,
+                kind: TypeCheckErrKind::Unbound(name),
+            }
+            .into();
+            Err(err.with_label(tree.span(), "expected its binding here"))
+        }
+        (None, _) => Ok(()),
+    }
+}
+
+/// Runs `src` in a fresh evaluator, for the tests below
+#[cfg(test)]
+fn run(src: &str) -> TResult<'_, TNoun> {
+    Evaluator::new().run(src).map(Option::unwrap)
+}
+
+#[test]
+fn repeat_forms() {
+    let run = |src| Evaluator::new().with_repeat_limit(100).run(src).map(Option::unwrap);
+    // Count
+    assert_eq!(run("3⍣(1+) 5"), Ok(TNoun::nat(8)));
+    assert_eq!(run("2 (3⍣×) 1"), Ok(TNoun::nat(8)));
+    // Fixed point
+    assert_eq!(run("100 ≍⍣(÷2)"), Ok(TNoun::nat(0)));
+    // Condition, both dyadic (old, new) and monadic (new)
+    assert_eq!(run("1 <⍣(×2)"), Ok(TNoun::nat(2)));
+    assert_eq!(run("1 (>20)⍣(×2)"), Ok(TNoun::nat(32)));
+    let err = run("1 ≍⍣(+1)").unwrap_err();
+    assert_eq!(err.kind(), &TErrorKind::RepeatLimit(100));
+}
+
+#[test]
+fn under_forms() {
+    let string = |s: &str| TArray::vector(s.chars().map(|c| TNoun::Atom(TAtom::Char(TChar(c)))).collect());
+    // Structural
+    assert_eq!(run("\"abc\" (↓1)⍜⇌"), Ok(string("acb")));
+    assert_eq!(run("\"abc\" (⊏0)⍜'z'"), Ok(string("zbc")));
+    // What's put back must fit where it came from
+    let err = run("\"abcd\" ⇌⍜(↑1)").unwrap_err();
+    assert_eq!(err.kind(), &TErrorKind::Length);
+    // Inverse. Subtraction can go negative, so undoing an addition gives an Int
+    assert_eq!(run("5 (+1)⍜(×2)"), Ok(TNoun::Atom(TAtom::Int(TInt(11)))));
+    assert_eq!(run("¯⍜(+1) 5"), Ok(TNoun::Atom(TAtom::Int(TInt(4)))));
+}
+
+#[test]
+fn logic() {
+    assert_eq!(run("1_1_0 ∧ 1_0_0"), Ok(TArray::vector(vec![TNoun::nat(1), TNoun::nat(0), TNoun::nat(0)])));
+    assert_eq!(run("1 ∧ 1_0"), Ok(TArray::vector(vec![TNoun::nat(1), TNoun::nat(0)])));
+    assert_eq!(run("2 ∧ 1").unwrap_err().kind(), &TErrorKind::Domain);
+}
+
+#[test]
+fn table_and_evert() {
+    let nats = |shape: Shape, ns: &[u64]| TArray::noun(shape, ns.iter().copied().map(TNoun::nat).collect());
+    assert_eq!(run("\"ab\" ⊞= \"abc\""), Ok(nats(vec![2, 3], &[1, 0, 0, 0, 1, 0])));
+    // Reverses each row rather than the order of the rows
+    assert_eq!(run("(\"ab\" ⊞= \"abc\") ⧋⇌"), Ok(nats(vec![2, 3], &[0, 0, 1, 0, 1, 0])));
+    assert_eq!(run("(\"ab\" ⊞= \"abc\") ⧋≢"), Ok(TNoun::nat(3)));
+}
+
+#[test]
+fn dfns() {
+    assert_eq!(run("f ← {α+1} ⋄ 2 f"), Ok(TNoun::nat(3)));
+    assert_eq!(run("g ← {α×ω}\n3 g 4"), Ok(TNoun::nat(12)));
+    assert_eq!(run("3 {X ← α+1 ⋄ X×X}"), Ok(TNoun::nat(16)));
+    // Inner dfns see the outer ones' arguments
+    assert_eq!(run("3 {{α+ψ} 4}"), Ok(TNoun::nat(7)));
+    let parse_err = |src| match run(src).unwrap_err().kind() {
+        TErrorKind::Parse(kind) => *kind,
+        kind => panic!("{kind:?} is not a parse error"),
+    };
+    assert_eq!(parse_err("{β+1} 2"), TParseErrKind::ArgumentOutOfScope);
+    assert_eq!(parse_err("α+1"), TParseErrKind::ArgumentOutOfScope);
+    assert_eq!(parse_err("{1}"), TParseErrKind::DfnNoArguments);
+    assert_eq!(parse_err("1 {{{{{{α}}}}}}"), TParseErrKind::DfnTooDeep);
+}
+
+#[test]
+fn trains() {
+    assert_eq!(run("\"abc\" ⦅≢ + ≢⦆"), Ok(TNoun::nat(6)));
+    assert_eq!(run("7 ⦅+ × -⦆ 3"), Ok(TNoun::Atom(TAtom::Int(TInt(40)))));
+    assert_eq!(run("2 ⦅+ ¯⦆ 3"), Ok(TNoun::Atom(TAtom::Int(TInt(-5)))));
+    assert_eq!(run("sq ← ⦅≢ × ≢⦆ ⋄ \"ab\" sq"), Ok(TNoun::nat(4)));
+    // Monadic tines may take their argument on different sides
+    assert_eq!(run("⦅3∘≤ ∧ ≥∘1⦆ 5"), Ok(TNoun::nat(1)));
+    assert_eq!(run("⦅3∘≤ ∧ ≥∘1⦆ 0"), Ok(TNoun::nat(0)));
+    assert_eq!(run("4 ⦅≥∘1 ∧ 3∘≤⦆"), Ok(TNoun::nat(1)));
+    assert_eq!(run("3 ⦅⇌ - ¯⦆"), Ok(TNoun::Atom(TAtom::Int(TInt(6)))));
+    let err = run("⦅≢ + -⦆").unwrap_err();
+    assert_eq!(err.kind(), &TErrorKind::Parse(TParseErrKind::BadTrain));
+}
+
+#[test]
+fn jots() {
+    let int = |n| Ok(TNoun::Atom(TAtom::Int(TInt(n))));
+    assert_eq!(run("5 ≥∘1"), Ok(TNoun::nat(1)));
+    assert_eq!(run("(3∘-) 5"), int(-2));
+    assert_eq!(run("\"abc\" ⇌∘≢"), Ok(TNoun::nat(3)));
+    assert_eq!(run("\"ab\" ≢∘+ 1"), Ok(TNoun::nat(3)));
+    assert_eq!(run("1 -∘≢ \"abc\""), int(-2));
+    assert_eq!(run("inc ← +∘1 ⋄ 2 inc inc"), Ok(TNoun::nat(4)));
+}
+
+#[test]
+fn strands() {
+    let nats = |ns: &[u64]| TArray::vector(ns.iter().copied().map(TNoun::nat).collect());
+    assert_eq!(run("1_2_3"), Ok(nats(&[1, 2, 3])));
+    assert_eq!(run("x ← 4 ⋄ 1_(2+3)_x ⇌"), Ok(nats(&[4, 5, 1])));
+    assert_eq!(run("(1_2)_\"ab\" ≢"), Ok(TNoun::nat(2)));
+    assert_eq!(run("1_2_3 ⊏ 1"), Ok(TNoun::nat(2)));
+    let err = |src| run(src).unwrap_err().kind().clone();
+    assert_eq!(err("1_ + 2"), TErrorKind::Parse(TParseErrKind::BadStrand));
+    assert_eq!(err("1_⇌"), TErrorKind::Parse(TParseErrKind::BadStrand));
+}
+
+#[test]
+fn brackets() {
+    let nats = |ns: &[u64]| ns.iter().copied().map(TNoun::nat).collect::<Vec<_>>();
+    assert_eq!(run("[1 ⋄ 2+3 ⋄ 4]"), Ok(TArray::vector(nats(&[1, 5, 4]))));
+    assert_eq!(run("[1_2 ⋄ 3_4 ⋄ 5_6] ⍴"), Ok(TArray::vector(nats(&[3, 2]))));
+    assert_eq!(run("[a⋄b⋄c] ← 1_2_3 ⋄ a+c"), Ok(TNoun::nat(4)));
+    assert_eq!(run("f ← {[x⋄y] ← ω ⋄ x-y} ⋄ f 5_3"), Ok(TNoun::Atom(TAtom::Int(TInt(2)))));
+    let err = |src| run(src).unwrap_err().kind().clone();
+    assert_eq!(err("[a⋄b+1] ← 1_2"), TErrorKind::Parse(TParseErrKind::BadPattern));
+    assert_eq!(err("[1 ⋄ 2_3]"), TErrorKind::Type(TypeCheckErrKind::RankMismatch { expected: 0, found: 1 }));
+}
+
+#[test]
+fn empty_cells() {
+    let run = |src: &str| Evaluator::new().run(src).map(Option::unwrap).map_err(|e| e.kind().clone());
+    let empty = |shape: Shape| Ok(TArray::noun(shape, vec![]));
+    // Two major cells, neither of which holds anything
+    let src = |f| format!("[0⇡ ⋄ 0⇡] {f}");
+    assert_eq!(run(&src("⇌")), empty(vec![2, 0]));
+    assert_eq!(run(&src("↑1")), empty(vec![1, 0]));
+    assert_eq!(run(&src("↓1")), empty(vec![1, 0]));
+    assert_eq!(run(&src("⊏1")), empty(vec![0]));
+    assert_eq!(run(&src("⇌⍜⇌")), empty(vec![2, 0]));
+    assert_eq!(run(&src("⊞+ 1_2")), empty(vec![2, 2, 0]));
+    assert_eq!(run(&src("⧋⇌")), empty(vec![2, 0]));
+    assert_eq!(run(&format!("[a ⋄ b] ← {} ⋄ a≢", src(""))), Ok(TNoun::nat(0)));
+    // And no major cells at all
+    assert_eq!(run("(0⇡) ⊞+ 1_2"), empty(vec![0, 2]));
+}
+
+#[test]
+fn data_types() {
+    let decls = "□ Suit ← ⟨ Club ⋄ Diamond ⋄ Heart ⋄ Spade ⟩
+                 □ Card ← ⟨ value: 'Nat ⋄ suit: 'Suit ⟩
+                 □ Player ← ⟨ Host ← ⟨ name: '(1, char) ⟩
+                              Guest ← ⟨ debt: 'Nat ⋄ hp: 'Nat ⟩ ⟩
+                 ";
+    let run_with = |src: &str| {
+        let src = format!("{decls}{src}");
+        Evaluator::new().run(&src).map(Option::unwrap).map_err(|e| e.kind().clone())
+    };
+    assert_eq!(run_with("(Card 7_Heart) .value"), Ok(TNoun::nat(7)));
+    assert_eq!(run_with("c ← Card [3 ⋄ Club] ⋄ c .suit ≍ Club"), Ok(TNoun::nat(1)));
+    assert_eq!(run_with("(Host \"bob\") .name ≢"), Ok(TNoun::nat(3)));
+    assert_eq!(run_with("Heart ≍ Spade"), Ok(TNoun::nat(0)));
+    // Only the variant a value is of has its fields
+    assert_eq!(run_with("p ← Host \"bob\" ⋄ p .debt"), Err(TErrorKind::Domain));
+    let err = Evaluator::new().run("1 □ T ← ⟨ A ⟩").unwrap_err();
+    assert_eq!(err.kind(), &TErrorKind::Parse(TParseErrKind::BadData));
+}
+
+#[test]
+fn matches() {
+    let decls = "□ Shape ← ⟨ Circle ← ⟨ r: 'Nat ⟩ ⋄ Rect ← ⟨ w: 'Nat ⋄ h: 'Nat ⟩ ⋄ Empty ⟩
+                 area ← ⊔⟨ Circle r → 3×r×r ⋄ Rect w h → w×h ⋄ Empty → 0 ⟩
+                 ";
+    let run = |src: &str| {
+        let src = format!("{decls}{src}");
+        Evaluator::new().run(&src).map(Option::unwrap).map_err(|e| e.kind().clone())
+    };
+    assert_eq!(run("(Rect 2_3) area"), Ok(TNoun::nat(6)));
+    assert_eq!(run("(Circle 2) area"), Ok(TNoun::nat(12)));
+    assert_eq!(run("Empty area"), Ok(TNoun::nat(0)));
+    assert_eq!(run("(Rect 2_3) ⊔⟨ Rect 1 h → h ⋄ Rect _ h → h+h ⋄ _ → 0 ⟩"), Ok(TNoun::nat(6)));
+    assert_eq!(run("4_5 ⊔⟨ [a ⋄ b] → a+b ⋄ _ → 0 ⟩"), Ok(TNoun::nat(9)));
+    assert_eq!(run("\"abc\" ⊔⟨ [x ⋄ y] → 1 ⋄ [x ⋄ 'b' ⋄ _] → x ⋄ _ → 'z' ⟩"), Ok(TNoun::Atom(TAtom::Char(TChar('a')))));
+    // Dfns are checked with what they're given, so only what the checker can't tell apart is left
+    assert_eq!(run("f ← {ω ⊔⟨ 3 → 1 ⟩} ⋄ f 5"), Err(TErrorKind::Type(TypeCheckErrKind::NonExhaustive(vec!["_".to_string()]))));
+    assert_eq!(run("f ← {ω ⊔⟨ [a ⋄ b ⋄ c] → 1 ⟩} ⋄ f 1_'a'"), Err(TErrorKind::NoMatch));
+    assert_eq!(run("1 ⊔⟨ 1 ⟩"), Err(TErrorKind::Parse(TParseErrKind::BadMatch)));
+}
+
+#[test]
+fn recovery() {
+    let src = "x ← 1 ¤ 2
+F ← {
+  y ← α
+  'a' + 'b'
+}
+z ← 2 F
+n ← 1 2
+w ← ⍳[1 ⋄ 2 ¤] ⋄ v ← w + x
+m ← x + n + v";
+    let errors = Evaluator::new().check_all(src).unwrap_err();
+    let kinds = errors.iter().map(|e| e.kind().clone()).collect::<Vec<_>>();
+    // Later statements still see x, F, n and w, so nothing else is reported
+    assert_eq!(
+        kinds,
+        [
+            TErrorKind::Parse(TParseErrKind::UnknownGlyph),
+            TErrorKind::Type(TypeCheckErrKind::NotNumeric(TAtomKind::Char)),
+            TErrorKind::Parse(TParseErrKind::CannotBind),
+            TErrorKind::Parse(TParseErrKind::UnknownGlyph),
+        ]
+    );
+}
+
+#[test]
+fn recovery_after_unclosed() {
+    let kinds = |src| {
+        let errors = Evaluator::new().check_all(src).unwrap_err();
+        errors.iter().map(|e| e.kind().clone()).collect::<Vec<_>>()
+    };
+    let unclosed = TErrorKind::Parse(TParseErrKind::UnclosedDelimiter);
+    let not_numeric = TErrorKind::Type(TypeCheckErrKind::NotNumeric(TAtomKind::Char));
+    // An open bracket only takes up to the next line that isn't indented
+    let src = "y ← (1 +
+z ← 'a' + 1
+w ← [1 ⋄ 2
+  ⋄ 3
+v ← 'b' + 1";
+    assert_eq!(kinds(src), [unclosed.clone(), not_numeric.clone(), unclosed, not_numeric.clone()]);
+    // Lines with only closing brackets are still part of what they close
+    let src = "F ← {
+  (α + 1
+}
+x ← 'a' + 1";
+    assert_eq!(kinds(src), [TErrorKind::Parse(TParseErrKind::UnexpectedCloser), not_numeric.clone()]);
+    // Uses of a name whose binding failed too badly to tell what it is aren't checked either
+    let src = "f ← +∘
+x ← 1 f
+g ← x + 1
+y ← 'a' + 1";
+    assert_eq!(kinds(src), [TErrorKind::Parse(TParseErrKind::CannotBind), not_numeric.clone()]);
+    // Unless it's bound again
+    let src = "f ← +∘
+f ← +1
+x ← 'a' f";
+    assert_eq!(&kinds(src)[1..], std::slice::from_ref(&not_numeric));
+    // Those whose binding only failed to type check can still be told apart
+    let src = "f ← +'a'
+x ← 1 f
+y ← x 2";
+    assert_eq!(kinds(src), [not_numeric, TErrorKind::Parse(TParseErrKind::CannotBind)]);
+}
+
+#[test]
+fn args_and_output() {
+    let args = ["a".to_string(), "bcd".to_string()];
+    let mut e = Evaluator::new().with_args(&args);
+    let known = e.check("⎕args").unwrap().unwrap().known();
+    assert_eq!(known, Known::new(Some(TAtomKind::Char), Some(vec![2, 3])));
+    assert_eq!(e.run("⎕args").unwrap().unwrap().to_string(), "a  \nbcd");
+    let shown = |src| Evaluator::new().run(src).unwrap().unwrap().to_string();
+    assert_eq!(shown("\"hi\""), "hi");
+    assert_eq!(shown("1 - 3"), "¯2");
+    assert_eq!(shown("[1_200 ⋄ 30_4]"), " 1 200\n30   4");
+}
+
+#[test]
+fn repl_lines() {
+    let mut e = Evaluator::new();
+    assert!(Evaluator::incomplete("F ← {\n  α + 1"));
+    assert!(!Evaluator::incomplete("F ← {\n  α + 1\n}"));
+    assert_eq!(e.run_shown("F ← {\n  α + 1\n}"), Ok(None));
+    let (val, ty) = e.run_shown("2 F").unwrap().unwrap();
+    assert_eq!((val.to_string(), ty.as_str()), ("3".to_string(), "'Nat"));
+    assert_eq!(e.run_shown("x ← 1_2").unwrap(), None);
+    assert_eq!(e.run_shown("x").unwrap().unwrap().1, "'(1, ≢=2, ⍴=2, Nat)");
+}
+
+#[test]
+fn workspace() {
+    let mut e = Evaluator::new();
+    e.run(": ∀T (Num T) 'T » F ↳ 'T").unwrap();
+    e.run("F ← {α + 1}\nx ← 1_2\n[a ⋄ b] ← x\ny ← 3").unwrap();
+    e.run("x ← 5 ⋄ F ← {α × 2}").unwrap();
+    assert_eq!(e.vars(), ["a", "b", "x", "y"]);
+    assert_eq!(e.fns(), ["F"]);
+    assert_eq!(e.type_of("1 F").unwrap().as_deref(), Some("'Nat"));
+    assert_eq!(e.type_of("z ← 1").unwrap().as_deref(), Some("'Nat"));
+    assert!(e.erase("y") && !e.erase("z"));
+    assert_eq!(e.vars(), ["a", "b", "x"]);
+    // The destructuring stays, as it still binds a and b
+    let saved = e.workspace();
+    assert_eq!(saved, "x ← 1_2\n[a ⋄ b] ← x\nx ← 5\n: ∀T (Num T) 'T » F ↳ 'T\nF ← {α × 2}\n");
+    let mut loaded = Evaluator::new();
+    loaded.run(&saved).unwrap();
+    assert_eq!(loaded.run("x F").unwrap(), Some(TNoun::nat(10)));
+    // An error in a dfn from an earlier input is put down to where it's called from
+    e.run("G ← {α ⊏ 1_2}").unwrap();
+    let err = e.run("x ← 9\nx G").unwrap_err();
+    assert_eq!((err.kind(), err.span().start), (&TErrorKind::Rank, "x ← 9\n".len()));
+}
+
+#[test]
+fn reloading() {
+    let mut e = Evaluator::new();
+    e.run("F ← {α + 1}\nG ← {(α F) + 1}\nH ← {α}").unwrap();
+    // Nothing changes if the new version fails
+    assert!(e.reload("F ← {α + 'a'} ⋄ 1 F").is_err());
+    assert_eq!(e.run("2 G").unwrap(), Some(TNoun::nat(4)));
+    let dependents = e.reload(": ∀T 'T » F ↳ 'Char\nF ← {α ⋄ 'a'}").unwrap();
+    assert_eq!(dependents, ["G ← {(α F) + 1}"]);
+    let err = e.type_of(&dependents[0]).unwrap_err();
+    assert_eq!(err.kind(), &TErrorKind::Type(TypeCheckErrKind::NotNumeric(TAtomKind::Char)));
+}
+
+#[test]
+fn spelled_out() {
+    let glyphs = ": ∀T '(⍤=1, T) » F ↳ '(⍤=1, T)\nF ← {α ⇌} ⋄ x₁ ← 3⇡ ⋄ x₁ F ⊏ 0";
+    let names = crate::glyphs::with_names(glyphs);
+    assert!(names.is_ascii());
+    assert_eq!(Evaluator::new().run(glyphs), Ok(Some(TNoun::nat(2))));
+    assert_eq!(Evaluator::new().run(&names), Evaluator::new().run(glyphs));
+    let hand_written = "F \\gets {\\alpha \\reverse} \\diamond x\\sub1 \\gets 3\\range \\diamond x\\sub1 F\\select 0";
+    assert_eq!(Evaluator::new().run(hand_written), Evaluator::new().run(glyphs));
+    // Errors point at the names
+    let err = Evaluator::new().check_all("1 \\gets 2 \\diamond 3 \\foo").unwrap_err();
+    assert_eq!(err.iter().map(|e| e.span().start).collect::<Vec<_>>(), [0, 21]);
+}
+
+#[test]
+fn examples() {
+    // The examples are written ahead of what's implemented, so they may only fail at the pieces
+    // still missing, listed here with what each one needs
+    let missing: [(&str, &[(&str, &str)]); 4] = [
+        (
+            "aoc",
+            &[
+                ("\"D₁\" ⎕NS", "namespaces, whose syntax is still to be decided"),
+                (": (", "signatures without quotes, with N and B for Nat and booleans and ⇒ for ↳"),
+                ("β/", "`/` reductions, and β in a dfn that isn't nested"),
+                ("*", "`*` for powers"),
+                ("⍤safe", "`⍤` as a modifier, calling a function on the cells of a rank"),
+            ],
+        ),
+        (
+            "bfs",
+            &[
+                (": '(⍤=2, B) » bfs « Nat", "B for booleans, and types without quotes"),
+                ("↳ '(⍤=1, Nat)", "the rest of that signature"),
+                ("⍺", "⍺ and ⍵ for α and ω"),
+            ],
+        ),
+        ("name", &[("@a", "`@` for character literals")]),
+        (
+            "quadratic",
+            &[
+                (": (≢=3, F)", "F for floats, and types without quotes"),
+                ("↳ (≢=2, F)", "the rest of that signature"),
+                ("⍺", "⍺ and ⍵ for α and ω"),
+            ],
+        ),
+    ];
+    for (example, pieces) in missing {
+        let src = std::fs::read_to_string(format!("examples/{example}.tqo")).expect("examples are there");
+        let errors = Evaluator::new().check_all(&src).err().unwrap_or_default();
+        let at = |e: &TError| src[e.span().start..].to_string();
+        for e in &errors {
+            assert!(pieces.iter().any(|(piece, _)| at(e).starts_with(piece)), "{example}: {e} isn't at a missing piece");
+        }
+        for (piece, needs) in pieces {
+            assert!(
+                errors.iter().any(|e| at(e).starts_with(piece)),
+                "{example} no longer fails at `{piece}`, which needed {needs}, so it can come off the list"
+            );
+        }
+    }
+}
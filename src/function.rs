@@ -1 +1,176 @@
+use std::rc::Rc;
+
 use crate::*;
+
+/// Anything that can be called
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TFunction {
+    Primitive(Primitive),
+    /// A dyadic verb with its alpha fixed, e.g. `1+`
+    AlphaBound {
+        alpha: Box<TNoun>,
+        f: Box<TFunction>,
+    },
+    /// A dyadic verb with its omega fixed, e.g. `+1`
+    OmegaBound {
+        f: Box<TFunction>,
+        omega: Box<TNoun>,
+    },
+    /// What a modifier makes out of its operand(s)
+    Derived {
+        modifier: Primitive,
+        alpha: Option<Box<TNoun>>,
+        omega: Option<Box<TNoun>>,
+    },
+    Dfn(Rc<Dfn>),
+    /// The verbs of a fork or an atop, see [`ExprTree::Train`]
+    Train(Vec<TNoun>),
+    /// Builds a value of a variant that has fields
+    Constructor { decl: Rc<DataDecl>, variant: usize },
+    /// `.name`
+    Field(String),
+    Match(Rc<Match>),
+}
+
+/// A dfn's code along with everything it closes over
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dfn {
+    pub(crate) body: Vec<ExprTree<'static>>,
+    /// Which of [`ARGUMENTS`] it takes
+    pub(crate) depth: usize,
+    /// The locals of the dfn call it was made in, if any
+    pub(crate) captured: HashMap<Ident, TNoun>,
+    /// What it was written in, which its spans refer to
+    pub(crate) src: Rc<str>,
+}
+
+/// The arms of a `⊔` along with everything it closes over, as with [`Dfn`]s
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Match {
+    pub(crate) arms: Vec<(Pattern<'static>, ExprTree<'static>)>,
+    pub(crate) captured: HashMap<Ident, TNoun>,
+    pub(crate) src: Rc<str>,
+}
+
+impl TFunction {
+    pub(crate) fn call<'src>(
+        &self,
+        ev: &mut Evaluator,
+        span: Sp<'src>,
+        alpha: Option<TNoun>,
+        omega: Option<TNoun>,
+    ) -> TResult<'src, TNoun> {
+        match self {
+            TFunction::Primitive(p) => p
+                .apply(alpha.as_ref(), omega.as_ref())
+                .map_err(|k| TError::new(k, span)),
+            TFunction::AlphaBound { alpha, f } => f.call(ev, span, Some((**alpha).clone()), omega),
+            TFunction::OmegaBound { f, omega } => f.call(ev, span, alpha, Some((**omega).clone())),
+            TFunction::Derived {
+                modifier,
+                alpha: l,
+                omega: r,
+            } => modifier.apply_modifier(ev, span, l.as_deref(), r.as_deref(), alpha, omega),
+            TFunction::Dfn(dfn) => ev.call_dfn(dfn, span, alpha, omega),
+            TFunction::Train(tines) => match &tines[..] {
+                [f, g] => {
+                    let x = f.call(ev, span, alpha, omega)?;
+                    g.call_monadic(ev, span, x)
+                }
+                // Each gets the argument on its own side
+                [f, g, h] if f.category() != h.category() => {
+                    let x = alpha.or(omega).expect("forks of monadic verbs are monadic");
+                    let l = f.call_monadic(ev, span, x.clone())?;
+                    let r = h.call_monadic(ev, span, x)?;
+                    g.call(ev, span, Some(l), Some(r))
+                }
+                [f, g, h] => {
+                    let l = f.call(ev, span, alpha.clone(), omega.clone())?;
+                    let r = h.call(ev, span, alpha, omega)?;
+                    g.call(ev, span, Some(l), Some(r))
+                }
+                _ => unreachable!("parser checks the length of trains"),
+            },
+            TFunction::Constructor { decl, variant } => {
+                let x = omega.expect("constructors are omega-monadic");
+                data::construct(decl, *variant, x).map_err(|k| TError::new(k, span))
+            }
+            TFunction::Field(name) => {
+                let x = alpha.expect("fields are alpha-monadic");
+                data::field(&x, name).map_err(|k| TError::new(k, span))
+            }
+            TFunction::Match(m) => ev.call_match(m, span, alpha.expect("matches are alpha-monadic")),
+        }
+    }
+    /// How `⍜` undoes this, along with the argument bound to it, if any
+    pub(crate) fn under(&self) -> Option<(Under, Option<&TNoun>)> {
+        match self {
+            TFunction::Primitive(p) => p.under(p.monadic_side()).map(|u| (u, None)),
+            TFunction::AlphaBound { alpha, f } => match **f {
+                TFunction::Primitive(p) => p.under(Side::Omega).map(|u| (u, Some(&**alpha))),
+                _ => None,
+            },
+            TFunction::OmegaBound { f, omega } => match **f {
+                TFunction::Primitive(p) => p.under(Side::Alpha).map(|u| (u, Some(&**omega))),
+                _ => None,
+            },
+            TFunction::Derived { .. }
+            | TFunction::Dfn(_)
+            | TFunction::Train(_)
+            | TFunction::Constructor { .. }
+            | TFunction::Field(_)
+            | TFunction::Match(_) => None,
+        }
+    }
+}
+
+impl TNoun {
+    pub(crate) fn function(f: TFunction, cat: Category) -> Self {
+        TNoun::Atom(match cat {
+            Category::Av => TAtom::AVerb(TAVerb(f)),
+            Category::Ov => TAtom::OVerb(TOVerb(f)),
+            Category::Dv => TAtom::DVerb(TDVerb(f)),
+            c => unreachable!("{c:?} is not a verb category"),
+        })
+    }
+    pub(crate) fn as_function(&self) -> Option<(&TFunction, Category)> {
+        match self {
+            TNoun::Atom(TAtom::AVerb(TAVerb(f))) => Some((f, Category::Av)),
+            TNoun::Atom(TAtom::OVerb(TOVerb(f))) => Some((f, Category::Ov)),
+            TNoun::Atom(TAtom::DVerb(TDVerb(f))) => Some((f, Category::Dv)),
+            _ => None,
+        }
+    }
+    pub(crate) fn category(&self) -> Category {
+        self.as_function().map_or(Category::A, |(_, c)| c)
+    }
+
+    pub(crate) fn call<'src>(
+        &self,
+        ev: &mut Evaluator,
+        span: Sp<'src>,
+        alpha: Option<TNoun>,
+        omega: Option<TNoun>,
+    ) -> TResult<'src, TNoun> {
+        match self.as_function() {
+            Some((f, _)) => f.call(ev, span, alpha, omega),
+            None => Err(TError::new(TErrorKind::Domain, span)),
+        }
+    }
+    /// Calls a monadic verb with `x` on whichever side it takes its argument from
+    pub(crate) fn call_monadic<'src>(&self, ev: &mut Evaluator, span: Sp<'src>, x: TNoun) -> TResult<'src, TNoun> {
+        match self.as_function() {
+            Some((f, Category::Av)) => f.call(ev, span, Some(x), None),
+            Some((f, Category::Ov)) => f.call(ev, span, None, Some(x)),
+            Some(_) => Err(TError::new(TErrorKind::Valence, span)),
+            None => Err(TError::new(TErrorKind::Domain, span)),
+        }
+    }
+    /// Nat booleans
+    pub(crate) fn truthy<'src>(&self, span: Sp<'src>) -> TResult<'src, bool> {
+        match self {
+            TNoun::Atom(TAtom::Nat(TNat(n @ (0 | 1)))) => Ok(*n == 1),
+            _ => Err(TError::new(TErrorKind::Domain, span)),
+        }
+    }
+}
@@ -0,0 +1,384 @@
+//! Every glyph of the language and how to type it, for input methods: a backtick then a key, as in
+//! other APLs, or a name completed from `\name`. Primitives come from their table, and the rest
+//! from the syntax they're part of.
+//!
+//! Code may also be written with the names themselves, e.g. `\reverse` for `⇌`, which are turned
+//! into glyphs before it's lexed. A name ends at the first character that can't be in it, or at a
+//! `;` for it to be followed by a letter or a digit, as in `x\sub1;y` for `x₁y`. Literals and the
+//! insides of comments are left as they are
+
+use crate::{parsing::ARGUMENTS, primitive::PRIMITIVES, *};
+
+/// A glyph and how to type it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Glyph {
+    pub glyph: char,
+    pub name: String,
+    /// Typed as a backtick then this key. Those on keyboards already don't have one
+    pub key: Option<char>,
+    /// What it's for, e.g. `reverse (α-monadic verb)`
+    pub meaning: String,
+}
+
+#[rustfmt::skip]
+const SYNTAX: &[(char, &str, Option<char>, &str)] = &[
+    ('←', "gets",        Some('['), "binds a name"),
+    ('⋄', "diamond",     Some('`'), "separates statements"),
+    ('⍝', "comment",     Some(','), "starts and ends a comment"),
+    ('⎕', "quad",        Some('l'), "starts a system name"),
+    ('⦅', "train",       Some('('), "starts a train"),
+    ('⦆', "endtrain",    Some(')'), "ends a train"),
+    ('⊔', "destructure", Some('D'), "matches a value against patterns"),
+    ('⟨', "arms",        Some('{'), "starts the arms of ⊔ or the entries of □"),
+    ('⟩', "endarms",     Some('}'), "ends the arms of ⊔ or the entries of □"),
+    ('→', "then",        Some(']'), "separates the pattern of an arm from its result"),
+    ('□', "data",        Some('#'), "declares a type"),
+    (':', "signature",   None,      "starts a signature"),
+    ('∀', "forall",      Some('A'), "introduces the type variables of a signature"),
+    ('»', "alphatype",   Some('>'), "follows α's type in a signature"),
+    ('«', "omegatype",   Some('<'), "precedes ω's type in a signature"),
+    ('↳', "output",      Some('L'), "precedes the output type in a signature"),
+    ('⍤', "rank",        Some('J'), "a rank in a type"),
+    ('′', "prime",       Some('\''), "may end a name"),
+];
+
+/// The dfn arguments, as named in the greek alphabet
+const GREEK: [&str; 10] = ["alpha", "omega", "beta", "psi", "gamma", "chi", "delta", "phi", "epsilon", "upsilon"];
+
+/// Every glyph, primitives first
+pub fn glyphs() -> Vec<Glyph> {
+    let primitives = PRIMITIVES.iter().map(|p| Glyph {
+        glyph: p.glyph,
+        name: p.alias.to_string(),
+        key: p.key,
+        meaning: format!("{} ({})", p.name.to_lowercase(), category_name(p.category)),
+    });
+    let syntax = SYNTAX.iter().map(|&(glyph, name, key, meaning)| Glyph {
+        glyph,
+        name: name.to_string(),
+        key,
+        meaning: meaning.to_string(),
+    });
+    let arguments = ARGUMENTS.iter().flat_map(|&(a, w)| [a, w]).zip(GREEK).enumerate();
+    let arguments = arguments.map(|(i, (glyph, name))| Glyph {
+        glyph: glyph.chars().next().expect("arguments are one letter"),
+        name: name.to_string(),
+        key: ["a", "w"].get(i).and_then(|k| k.chars().next()),
+        meaning: format!("{} argument of a dfn nested {} deep", ["left", "right"][i % 2], i / 2 + 1),
+    });
+    let subscripts = ('₀'..='₉').zip('0'..='9').map(|(glyph, digit)| Glyph {
+        glyph,
+        name: format!("sub{digit}"),
+        key: None,
+        meaning: "may end a name".to_string(),
+    });
+    primitives.chain(syntax).chain(arguments).chain(subscripts).collect()
+}
+
+/// The glyph typed as a backtick then `key`
+pub fn by_key(key: char) -> Option<char> {
+    glyphs().into_iter().find(|g| g.key == Some(key)).map(|g| g.glyph)
+}
+
+/// The glyphs whose names start with `prefix`, exact matches first
+pub fn by_name(prefix: &str) -> Vec<Glyph> {
+    let mut found = glyphs().into_iter().filter(|g| g.name.starts_with(prefix)).collect::<Vec<_>>();
+    found.sort_by_key(|g| g.name != prefix);
+    found
+}
+
+/// `src` with every glyph that isn't ASCII spelled out as `\\name`, which [`with_glyphs`] turns
+/// back into the same `src`. A name is followed by `;` where what comes next would otherwise be read
+/// as part of it or as the `;` that ends it, and backslashes in comments are doubled so that only
+/// the `\\comment` that ends one is read as a name
+pub fn with_names(src: &str) -> String {
+    let glyphs = glyphs();
+    let mut out = String::with_capacity(src.len());
+    let mut pos = 0;
+    let mut closing = false;
+    while let Some(c) = src[pos..].chars().next() {
+        if let Some(len) = literal(&src[pos..]) {
+            out.push_str(&src[pos..pos + len]);
+            pos += len;
+            continue;
+        }
+        pos += c.len_utf8();
+        match glyphs.iter().find(|g| g.glyph == c && !c.is_ascii()) {
+            Some(g) => {
+                out.push('\\');
+                out.push_str(&g.name);
+                if src[pos..].starts_with(|c: char| c.is_ascii_alphanumeric() || c == ';') {
+                    out.push(';');
+                }
+            }
+            None => out.push(c),
+        }
+        if c == '⍝' && !std::mem::take(&mut closing) {
+            let len = comment(&src[pos..]);
+            out.push_str(&src[pos..pos + len].replace('\\', "\\\\"));
+            pos += len;
+            closing = true;
+        }
+    }
+    out
+}
+
+/// `src` with every `\\name` turned into its glyph, see [`with_names`]
+pub fn with_glyphs(src: &str) -> String {
+    respell(src).text
+}
+
+/// Code with its glyphs spelled out turned into glyphs, see [`respell`]
+pub(crate) struct Respelled {
+    pub(crate) text: String,
+    /// Where each glyph ends in `text` and where its name ends in the original
+    ends: Vec<(usize, usize)>,
+}
+
+impl Respelled {
+    /// Where `pos` in the text is in the original
+    pub(crate) fn original(&self, pos: usize) -> usize {
+        match self.ends.partition_point(|&(end, _)| end <= pos) {
+            0 => pos,
+            i => pos - self.ends[i - 1].0 + self.ends[i - 1].1,
+        }
+    }
+}
+
+/// Turns every `\\name` in `src` into its glyph, keeping track of where they were. Unknown names
+/// are left for the lexer to complain about
+pub(crate) fn respell(src: &str) -> Respelled {
+    let glyphs = glyphs();
+    let mut text = String::with_capacity(src.len());
+    let mut ends = vec![];
+    let mut pos = 0;
+    let mut closing = false;
+    while let Some(c) = src[pos..].chars().next() {
+        if let Some(len) = literal(&src[pos..]) {
+            text.push_str(&src[pos..pos + len]);
+            pos += len;
+            continue;
+        }
+        let name = src[pos + c.len_utf8()..].split(|c: char| !c.is_ascii_alphanumeric()).next().unwrap_or("");
+        let spelled = c == '\\';
+        let glyph = match glyphs.iter().find(|g| spelled && g.name == name) {
+            Some(g) => {
+                pos += 1 + name.len();
+                if src[pos..].starts_with(';') {
+                    pos += 1;
+                }
+                text.push(g.glyph);
+                ends.push((text.len(), pos));
+                g.glyph
+            }
+            None => {
+                pos += c.len_utf8();
+                text.push(c);
+                c
+            }
+        };
+        if glyph == '⍝' && !std::mem::take(&mut closing) {
+            match spelled {
+                true => pos += spelled_comment(&src[pos..], pos, &mut text, &mut ends),
+                false => {
+                    let len = comment(&src[pos..]);
+                    text.push_str(&src[pos..pos + len]);
+                    pos += len;
+                }
+            }
+            closing = true;
+        }
+    }
+    Respelled { text, ends }
+}
+
+/// Pushes the inside of the comment at the start of `rest`, which [`with_names`] wrote, onto
+/// `text`, giving back how long it was. It ends where a glyph would or at `\\comment`, and its
+/// doubled backslashes are single again. `start` is where `rest` is in the original
+fn spelled_comment(rest: &str, start: usize, text: &mut String, ends: &mut Vec<(usize, usize)>) -> usize {
+    let mut pos = 0;
+    loop {
+        let len = rest[pos..].find(['\n', '⍝', '\\']).unwrap_or(rest.len() - pos);
+        text.push_str(&rest[pos..pos + len]);
+        pos += len;
+        let after = &rest[pos..];
+        if after.starts_with("\\\\") {
+            text.push('\\');
+            pos += 2;
+            ends.push((text.len(), start + pos));
+        } else if after.starts_with('\\') && after[1..].split(|c: char| !c.is_ascii_alphanumeric()).next() != Some("comment") {
+            text.push('\\');
+            pos += 1;
+        } else {
+            return pos;
+        }
+    }
+}
+
+/// How long the string or char literal at the start of `rest` is, if it starts with one. Quotes
+/// that don't make a char literal are those of types in signatures
+fn literal(rest: &str) -> Option<usize> {
+    let mut chars = rest.char_indices();
+    match chars.next()?.1 {
+        '"' => {
+            let mut escaped = false;
+            for (i, c) in chars {
+                match c {
+                    _ if escaped => escaped = false,
+                    '\\' => escaped = true,
+                    '"' => return Some(i + 1),
+                    _ => {}
+                }
+            }
+            Some(rest.len())
+        }
+        '\'' => {
+            match chars.next()?.1 {
+                '\'' => return None,
+                '\\' => {
+                    chars.next()?;
+                }
+                _ => {}
+            }
+            let (i, c) = chars.next()?;
+            (c == '\'').then_some(i + 1)
+        }
+        _ => None,
+    }
+}
+
+/// How long the inside of the comment at the start of `rest` is, up to the end of the line or the
+/// `⍝` that ends it
+fn comment(rest: &str) -> usize {
+    rest.find(['\n', '⍝']).unwrap_or(rest.len())
+}
+
+/// What the glyph `word` is, or is named by it, e.g. `⍜` or `under`, for `)help`
+pub fn help(word: &str) -> Option<String> {
+    let word = word.strip_prefix('\\').unwrap_or(word);
+    let g = glyphs().into_iter().find(|g| g.name == word || word.chars().eq([g.glyph]))?;
+    let key = g.key.map(|k| format!(", `{k}")).unwrap_or_default();
+    Some(match PRIMITIVES.iter().find(|p| p.glyph == g.glyph) {
+        Some(p) => format!("{} {} (\\{}{key}), {}: {}", p.glyph, p.name, p.alias, category_name(p.category), p.doc),
+        None => format!("{} \\{}{key}: {}", g.glyph, g.name, g.meaning),
+    })
+}
+
+/// The reference of the primitives in the README, as an org table made from their table. `tqo docs`
+/// prints it, and a test checks that the README has it as is
+pub fn docs() -> String {
+    let header = ["Glyph", "Name", "Typed as", "Kind", "⍜ by", "What it gives"].map(String::from);
+    let rows = PRIMITIVES.iter().map(|p| {
+        let key = match p.key {
+            Some(k @ ('|' | '=')) => format!(" or =`={}", org_glyph(k)),
+            Some(k) => format!(" or =`{k}="),
+            None => String::new(),
+        };
+        // Undone the same way on both sides more often than not
+        let mut under = vec![];
+        for (_, how) in p.under {
+            let how = match how {
+                Under::Structural => "structural".to_string(),
+                Under::Inverse(inverse, _) => org_glyph(inverse.info().glyph),
+            };
+            if !under.contains(&how) {
+                under.push(how);
+            }
+        }
+        [
+            org_glyph(p.glyph),
+            p.name.to_string(),
+            format!("=\\{}={key}", p.alias),
+            category_name(p.category).to_string(),
+            under.join(", "),
+            p.doc.to_string(),
+        ]
+    });
+    let rows = std::iter::once(header).chain(rows).collect::<Vec<_>>();
+    // Padded as org mode would align it
+    let widths = (0..6).map(|i| rows.iter().map(|r| r[i].chars().count()).max().unwrap_or(0)).collect::<Vec<_>>();
+    let line = |row: &[String; 6]| {
+        let cells = row.iter().zip(&widths).map(|(cell, &w)| format!(" {cell:w$} "));
+        format!("|{}|\n", cells.collect::<Vec<_>>().join("|"))
+    };
+    let rule = widths.iter().map(|&w| "-".repeat(w + 2)).collect::<Vec<_>>().join("+");
+    let mut table = line(&rows[0]) + &format!("|{rule}|\n");
+    for row in &rows[1..] {
+        table += &line(row);
+    }
+    table
+}
+
+/// How `glyph` is written in an org table, as entities for those that would break it or its markup
+pub(crate) fn org_glyph(glyph: char) -> String {
+    match glyph {
+        '=' => "\\equal".to_string(),
+        '|' => "\\vert".to_string(),
+        g => g.to_string(),
+    }
+}
+
+fn category_name(cat: Category) -> &'static str {
+    match cat {
+        Category::Av => "α-monadic verb",
+        Category::Ov => "ω-monadic verb",
+        Category::Dv => "dyadic verb",
+        Category::Aa => "α-monadic modifier",
+        Category::Oa => "ω-monadic modifier",
+        Category::Da | Category::Jot => "dyadic modifier",
+        c => unreachable!("primitives can't be of category {c:?}"),
+    }
+}
+
+#[test]
+fn unambiguous() {
+    let all = glyphs();
+    for (i, g) in all.iter().enumerate() {
+        for other in &all[i + 1..] {
+            assert_ne!(g.glyph, other.glyph);
+            assert_ne!(g.name, other.name);
+            assert!(g.key.is_none() || g.key != other.key, "{} and {} share a key", g.glyph, other.glyph);
+        }
+    }
+    assert_eq!(by_key('J'), Some('⍤'));
+    assert_eq!(by_name("sub1")[0].glyph, '₁');
+    assert_eq!(by_name("under")[0].glyph, '⍜');
+    assert_eq!(
+        help("\\under").as_deref(),
+        Some("⍜ Under (\\under, `U), dyadic modifier: ω applied to what α gives, which is then put back")
+    );
+}
+
+#[test]
+fn reference() {
+    let readme = include_str!("../README.org");
+    let (_, reference) = readme.split_once("** Reference").expect("the README has a reference");
+    assert!(reference.contains(&docs()), "the README's reference is out of date, see `tqo docs`");
+}
+
+#[test]
+fn spelled_out() {
+    let src = "x₁ ← ⍴⇌ 'a' ⍝ ⍴ stays ⍝ ⋄ F ← {α+¯1}\nx′ ← \"⍳\\\"⍴\" ⍳";
+    let names = with_names(src);
+    assert_eq!(
+        names,
+        "x\\sub1 \\gets \\shape\\reverse 'a' \\comment ⍴ stays \\comment \\diamond F \\gets {\\alpha+\\negate;1}\nx\\prime \\gets \"⍳\\\"⍴\" ⍳"
+    );
+    assert_eq!(with_glyphs(&names), src);
+    for example in ["aoc", "bfs", "name", "quadratic"] {
+        let src = std::fs::read_to_string(format!("examples/{example}.tqo")).expect("examples are there");
+        assert_eq!(with_glyphs(&with_names(&src)), src);
+    }
+    // As the README has it: what ends a name is kept
+    assert_eq!(with_glyphs("5\\range\\reverse"), "5⇡⇌");
+    assert_eq!(with_glyphs("5\\range \\reverse"), "5⇡ ⇌");
+    // A `;` or a `\\comment` that was there all along stays as it was
+    for src in ["⍴;1", "x ← 1 ⍝ spelled \\comment in ascii", "⍝ ends in \\⍝ 1", "⍝ \\\\comment ⍝;⍴"] {
+        assert_eq!(with_glyphs(&with_names(src)), src, "{src}");
+        assert_eq!(with_glyphs(src), src, "{src}");
+    }
+    assert_eq!(with_names("⍴;1"), "\\shape;;1");
+    let respelled = respell("\\shape;x + \\reverse");
+    assert_eq!(respelled.text, "⍴x + ⇌");
+    assert_eq!([3, 4, 7, 10].map(|pos| respelled.original(pos)), [7, 8, 11, 19]);
+}
@@ -1,16 +1,28 @@
-use std::{collections::HashMap, convert::Infallible, default, error, fmt::Display};
+use std::{collections::HashMap, convert::Infallible, fmt::Display, rc::Rc};
 
 use ast::Sp;
+use data::{DataDecl, DataId};
+use function::{Dfn, Match, TFunction};
+use primitive::Under;
 use parsing::*;
+use typecheck::*;
 mod ast;
+mod data;
+mod diagnostic;
+mod eval;
 mod function;
+pub mod glyphs;
 mod parsing;
 mod primitive;
+mod signature;
 mod typecheck;
 
+pub use diagnostic::{Diagnostic, Label, Severity};
+pub use eval::Evaluator;
+
 pub enum Item<'src> {
     Expr(ExprTree<'src>),
-    TypeDef(Typedef),
+    TypeDef(Box<Typedef>),
 }
 
 /// Specifies rank, length, etc
@@ -20,61 +32,154 @@ pub struct Typedef {
 }
 
 /// The type of any aray
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct TType {
     alpha: Option<InputTypeSpecifier>,
     omega: Option<InputTypeSpecifier>,
     output: Option<OutputTypeSpecifier>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct InputTypeSpecifier {
+    scalar: Option<TypeLevelExpr>,
     rank: Option<TypeLevelExpr>,
     length: Option<TypeLevelExpr>,
     shape: Option<TypeLevelExpr>,
+    /// The names of the axes
+    axes: Option<TypeLevelExpr>,
 }
 /// Each of {rank, length, shape} can be computed as a TypeLevelExpr that
 /// may only take as unknowns the typelevel specifications from the input(s)
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct OutputTypeSpecifier {
-    rank: Option<Box<dyn Fn(Option<u32>, Option<u32>) -> u32>>,
-    length: Option<Box<dyn Fn(Option<u32>, Option<u32>) -> u32>>,
-    shape: Option<Box<dyn Fn(Option<Shape>, Option<Shape>) -> Shape>>,
+    scalar: Option<TypeLevelExpr>,
+    rank: Option<TypeLevelExpr>,
+    length: Option<TypeLevelExpr>,
+    shape: Option<TypeLevelExpr>,
+    axes: Option<TypeLevelExpr>,
+}
+
+/// A type variable of a signature. Each use of the signature gets its own instance of it, so that
+/// different calls don't constrain each other
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TypeVar {
+    name: String,
+    instance: u32,
+    /// What it may stand for, from the constraints of its signature
+    classes: Vec<Class>,
+}
+
+/// A constraint on the scalar type a [`TypeVar`] stands for, e.g. `(Num T)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Class {
+    /// Compared with `=`, which all but functions are
+    Eq,
+    /// Ordered, which numbers and characters are
+    Ord,
+    /// Able to be computed with arithmetically
+    Num,
+}
+
+/// Which input a [`TypeLevelExpr`] refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Alpha,
+    Omega,
 }
 
 // Does not allow '=', allows stuff like reverse, join, etc.
-pub struct TypeLevelExpr {}
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeLevelExpr {
+    /// A known rank or length
+    Nat(u32),
+    /// A known shape
+    Shape(Vec<u32>),
+    /// A known scalar type
+    Scalar(TAtomKind),
+    /// Known names of the axes, `None` for those without one
+    Axes(Vec<Option<String>>),
+    /// A type variable, bound by unification at each call site
+    Var(TypeVar),
+    RankOf(Side),
+    LengthOf(Side),
+    ShapeOf(Side),
+    ScalarOf(Side),
+    AxesOf(Side),
+    Add(Box<TypeLevelExpr>, Box<TypeLevelExpr>),
+    Sub(Box<TypeLevelExpr>, Box<TypeLevelExpr>),
+    Reverse(Box<TypeLevelExpr>),
+    /// Whichever of the two isn't a scalar's (i.e. scalar extension). Works on ranks, shapes and axes
+    Pervade(Box<TypeLevelExpr>, Box<TypeLevelExpr>),
+    /// The smallest numeric type both scalar types fit in
+    Promote(Box<TypeLevelExpr>, Box<TypeLevelExpr>),
+    /// The scalar type, but able to go negative
+    Signed(Box<TypeLevelExpr>),
+}
 
-#[derive(Debug, Clone, Copy)]
-struct TError<'src> {
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TError<'src> {
     span: Sp<'src>,
     kind: TErrorKind,
+    /// Other places that have to do with the error, and how
+    related: Vec<(Sp<'src>, String)>,
 }
 
-#[derive(Debug, Clone, Copy)]
-enum TErrorKind {
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TErrorKind {
     EmptyExpr,
+    Parse(TParseErrKind),
+    Type(TypeCheckErrKind),
+    /// Name was read before being assigned to
+    UnknownName,
+    /// Wrong scalar type for the operation (e.g. adding chars)
+    Domain,
+    /// Arguments' ranks don't agree
+    Rank,
+    /// Arguments' shapes don't agree
+    Length,
+    /// Number doesn't fit in the type it was computed in
+    Overflow,
+    /// A function was given the wrong number of arguments
+    Valence,
+    /// `⍣` went through this many iterations without its condition holding
+    RepeatLimit(usize),
+    /// An index past the end of an axis
+    Index,
+    /// `⍜` can't put back what its function did
+    NoInverse,
+    /// No arm of a `⊔` matched
+    NoMatch,
 }
 
 type TResult<'src, T> = Result<T, TError<'src>>;
 
-#[rustfmt::skip] #[derive(Clone, Debug, PartialEq, Eq)] struct TNat(Infallible);
-#[rustfmt::skip] #[derive(Clone, Debug, PartialEq, Eq)] struct TInt(Infallible);
-#[rustfmt::skip] #[derive(Clone, Debug, PartialEq, Eq)] struct TU8(Infallible);
-#[rustfmt::skip] #[derive(Clone, Debug, PartialEq, Eq)] struct TU16(Infallible);
-#[rustfmt::skip] #[derive(Clone, Debug, PartialEq, Eq)] struct TI8(Infallible);
-#[rustfmt::skip] #[derive(Clone, Debug, PartialEq, Eq)] struct TI16(Infallible);
-#[rustfmt::skip] #[derive(Clone, Debug, PartialEq, Eq)] struct TChar(Infallible);
-#[rustfmt::skip] #[derive(Clone, Debug, PartialEq, Eq)] struct TAVerb(Infallible);
-#[rustfmt::skip] #[derive(Clone, Debug, PartialEq, Eq)] struct TOVerb(Infallible);
-#[rustfmt::skip] #[derive(Clone, Debug, PartialEq, Eq)] struct TDVerb(Infallible);
-#[rustfmt::skip] #[derive(Clone, Debug, PartialEq, Eq)] struct TAAdverb(Infallible);
-#[rustfmt::skip] #[derive(Clone, Debug, PartialEq, Eq)] struct TOAdverb(Infallible);
-#[rustfmt::skip] #[derive(Clone, Debug, PartialEq, Eq)] struct TDAdverb(Infallible);
-#[rustfmt::skip] #[derive(Clone, Debug, PartialEq, Eq)] struct TSumType(Infallible);
+#[rustfmt::skip] #[derive(Clone, Copy, Debug, PartialEq, Eq)] pub struct TNat(pub(crate) u64);
+#[rustfmt::skip] #[derive(Clone, Copy, Debug, PartialEq, Eq)] pub struct TInt(pub(crate) i64);
+#[rustfmt::skip] #[derive(Clone, Copy, Debug, PartialEq, Eq)] pub struct TU8(pub(crate) u8);
+#[rustfmt::skip] #[derive(Clone, Copy, Debug, PartialEq, Eq)] pub struct TU16(pub(crate) u16);
+#[rustfmt::skip] #[derive(Clone, Copy, Debug, PartialEq, Eq)] pub struct TI8(pub(crate) i8);
+#[rustfmt::skip] #[derive(Clone, Copy, Debug, PartialEq, Eq)] pub struct TI16(pub(crate) i16);
+#[rustfmt::skip] #[derive(Clone, Copy, Debug, PartialEq, Eq)] pub struct TChar(pub(crate) char);
+#[rustfmt::skip] #[derive(Clone, Debug, PartialEq, Eq)] pub struct TAVerb(pub(crate) TFunction);
+#[rustfmt::skip] #[derive(Clone, Debug, PartialEq, Eq)] pub struct TOVerb(pub(crate) TFunction);
+#[rustfmt::skip] #[derive(Clone, Debug, PartialEq, Eq)] pub struct TDVerb(pub(crate) TFunction);
+#[rustfmt::skip] #[derive(Clone, Debug, PartialEq, Eq)] pub struct TAAdverb(Infallible);
+#[rustfmt::skip] #[derive(Clone, Debug, PartialEq, Eq)] pub struct TOAdverb(Infallible);
+#[rustfmt::skip] #[derive(Clone, Debug, PartialEq, Eq)] pub struct TDAdverb(Infallible);
 
+/// A value of a user-defined type, see [`data`]
 #[derive(Clone, Debug, PartialEq, Eq)]
-struct Ident(String);
+pub struct TSumType {
+    pub(crate) decl: Rc<DataDecl>,
+    pub(crate) variant: usize,
+    pub(crate) fields: Vec<TNoun>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Ident(pub(crate) String);
 
 #[derive(Clone, Debug, PartialEq, Eq)]
-enum TAtom {
+pub enum TAtom {
     /// Arbitrary precision natural (including zero)
     Nat(TNat),
     /// Arbitrary precision integer
@@ -86,21 +191,25 @@ enum TAtom {
     /// Signed eight-bit number
     I8(TI8),
     /// Signed sixteen-bit number
-    I16(TU16),
+    I16(TI16),
     /// Unicode character
     Char(TChar),
     /// alpha-monadic verb
     AVerb(TAVerb),
     /// omega-monadic verb
     OVerb(TOVerb),
+    /// dyadic verb
+    DVerb(TDVerb),
     /// alpha-monadic adverb
-    AAdverb(TAVerb),
+    AAdverb(TAAdverb),
     /// omega-monadic adverb
-    OAdverb(TOVerb),
+    OAdverb(TOAdverb),
+    /// dyadic adverb
+    DAdverb(TDAdverb),
     SumType(TSumType),
 }
-#[derive(Clone, Debug, PartialEq, Eq)]
-enum TAtomKind {
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TAtomKind {
     /// Arbitrary precision natural (including zero)
     Nat,
     /// Arbitrary precision integer
@@ -116,26 +225,27 @@ enum TAtomKind {
     AAdverb,
     OAdverb,
     DDyadic,
-    SumType,
+    SumType(DataId),
 }
 
 type Shape = Vec<u32>; // TODO: Turn this into a tinyvec
 #[derive(Clone, Debug, PartialEq, Eq)]
-struct TArray {
+pub struct TArray {
     shape: Shape,
     data: Vec<TNoun>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
-enum TNoun {
+pub enum TNoun {
     Atom(TAtom),
     Array(TArray),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 struct TEnv(HashMap<Ident, TNoun>);
 
-enum Primitive {
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Primitive {
     Negate,
     Not,
     Sign,
@@ -144,35 +254,254 @@ enum Primitive {
     Multiply,
     Divide,
     AbsoluteValue,
-    Sine,
     Reciprocal,
     Sqrt,
     Modulo,
     Maximum,
     Minimum,
+    Equals,
+    NotEquals,
+    LessThan,
+    LessOrEqual,
+    GreaterThan,
+    GreaterOrEqual,
+    And,
+    Left,
+    Right,
+    Range,
+    Reverse,
+    Tally,
+    Match,
+    Notmatch,
+    Shape,
+    Select,
+    Pick,
+    Take,
+    Drop,
+    Transpose,
+    Table,
+    Evert,
+    Repeat,
+    Under,
+    Jot,
 }
 
-impl Default for TEnv {
-    fn default() -> Self {
-        let hs = HashMap::from([(1, 2)]);
-        todo!()
+impl<'src> From<TParseErr<'src>> for TError<'src> {
+    fn from(value: TParseErr<'src>) -> Self {
+        Self::new(TErrorKind::Parse(value.kind), value.span)
     }
 }
 
-impl<'src> From<TParseErr<'src>> for TError<'src> {
-    fn from(value: TParseErr<'src>) -> Self {
-        todo!()
+impl<'src> From<TypeCheckErr<'src>> for TError<'src> {
+    fn from(value: TypeCheckErr<'src>) -> Self {
+        Self::new(TErrorKind::Type(value.kind), value.span)
     }
 }
 
 impl<'src> Display for TError<'src> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        todo!()
+        write!(f, "{} at {}..{}", self.kind, self.span.start, self.span.end)
+    }
+}
+
+impl Display for TErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TErrorKind::EmptyExpr => write!(f, "empty expression"),
+            TErrorKind::Parse(kind) => write!(f, "{kind}"),
+            TErrorKind::Type(kind) => write!(f, "{kind}"),
+            TErrorKind::UnknownName => write!(f, "unknown name"),
+            TErrorKind::Domain => write!(f, "domain error"),
+            TErrorKind::Rank => write!(f, "rank error"),
+            TErrorKind::Length => write!(f, "length error"),
+            TErrorKind::Overflow => write!(f, "overflow"),
+            TErrorKind::Valence => write!(f, "wrong number of arguments"),
+            TErrorKind::RepeatLimit(n) => write!(f, "⍣ gave up after {n} iterations"),
+            TErrorKind::Index => write!(f, "index out of bounds"),
+            TErrorKind::NoInverse => write!(f, "⍜ can't undo this function"),
+            TErrorKind::NoMatch => write!(f, "no arm of ⊔ matched"),
+        }
+    }
+}
+
+impl TErrorKind {
+    /// Identifies the kind of error for tooling: `P` for parse errors, `T` for type errors and `R` for
+    /// those that happen while running. Never reused or renumbered
+    pub fn code(&self) -> &'static str {
+        match self {
+            TErrorKind::EmptyExpr => "P000",
+            TErrorKind::Parse(kind) => kind.code(),
+            TErrorKind::Type(kind) => kind.code(),
+            TErrorKind::UnknownName => "R001",
+            TErrorKind::Domain => "R002",
+            TErrorKind::Rank => "R003",
+            TErrorKind::Length => "R004",
+            TErrorKind::Overflow => "R005",
+            TErrorKind::Valence => "R006",
+            TErrorKind::RepeatLimit(_) => "R007",
+            TErrorKind::Index => "R008",
+            TErrorKind::NoInverse => "R009",
+            TErrorKind::NoMatch => "R010",
+        }
     }
 }
 
 impl<'src> TError<'src> {
     pub fn new(kind: TErrorKind, span: Sp<'src>) -> Self {
-        Self { span, kind }
+        Self {
+            span,
+            kind,
+            related: vec![],
+        }
+    }
+    /// Points out another place that has to do with the error
+    pub fn with_label(mut self, span: Sp<'src>, message: impl Into<String>) -> Self {
+        self.related.push((span, message.into()));
+        self
+    }
+    pub fn kind(&self) -> &TErrorKind {
+        &self.kind
+    }
+    /// The same error in another version of the source, where `at` says each position went
+    pub(crate) fn moved<'to>(self, at: impl Fn(usize) -> usize) -> TError<'to> {
+        let moved = |span: Sp| Sp::new(at(span.start), at(span.end));
+        TError {
+            span: moved(self.span),
+            kind: self.kind,
+            related: self.related.into_iter().map(|(span, message)| (moved(span), message)).collect(),
+        }
+    }
+    pub fn span(&self) -> Sp<'src> {
+        self.span
+    }
+}
+
+impl TAtom {
+    pub fn kind(&self) -> TAtomKind {
+        match self {
+            TAtom::Nat(_) => TAtomKind::Nat,
+            TAtom::Int(_) => TAtomKind::Int,
+            TAtom::U8(_) => TAtomKind::U8,
+            TAtom::U16(_) => TAtomKind::U16,
+            TAtom::I8(_) => TAtomKind::I8,
+            TAtom::I16(_) => TAtomKind::I16,
+            TAtom::Char(_) => TAtomKind::Char,
+            TAtom::AVerb(_) => TAtomKind::AVerb,
+            TAtom::OVerb(_) => TAtomKind::OVerb,
+            TAtom::DVerb(_) => TAtomKind::DVerb,
+            TAtom::AAdverb(_) => TAtomKind::AAdverb,
+            TAtom::OAdverb(_) => TAtomKind::OAdverb,
+            TAtom::DAdverb(_) => TAtomKind::DDyadic,
+            TAtom::SumType(v) => TAtomKind::SumType(v.decl.id),
+        }
+    }
+}
+
+impl TNoun {
+    pub fn nat(n: u64) -> Self {
+        TNoun::Atom(TAtom::Nat(TNat(n)))
+    }
+    pub fn shape(&self) -> &[u32] {
+        match self {
+            TNoun::Atom(_) => &[],
+            TNoun::Array(arr) => &arr.shape,
+        }
+    }
+}
+
+/// Negative numbers are written with a high minus, as they're read, and functions as their glyph
+/// or as `ƒ` if they aren't a primitive
+impl Display for TAtom {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let int = |f: &mut std::fmt::Formatter<'_>, n: i64| match n < 0 {
+            true => write!(f, "¯{}", n.unsigned_abs()),
+            false => write!(f, "{n}"),
+        };
+        let function = |f: &mut std::fmt::Formatter<'_>, fun: &TFunction| match fun {
+            TFunction::Primitive(p) => write!(f, "{}", p.glyph()),
+            _ => write!(f, "ƒ"),
+        };
+        match self {
+            TAtom::Nat(n) => write!(f, "{}", n.0),
+            TAtom::Int(n) => int(f, n.0),
+            TAtom::U8(n) => write!(f, "{}", n.0),
+            TAtom::U16(n) => write!(f, "{}", n.0),
+            TAtom::I8(n) => int(f, n.0.into()),
+            TAtom::I16(n) => int(f, n.0.into()),
+            TAtom::Char(c) => write!(f, "{}", c.0),
+            TAtom::AVerb(v) => function(f, &v.0),
+            TAtom::OVerb(v) => function(f, &v.0),
+            TAtom::DVerb(v) => function(f, &v.0),
+            TAtom::AAdverb(a) => match a.0 {},
+            TAtom::OAdverb(a) => match a.0 {},
+            TAtom::DAdverb(a) => match a.0 {},
+            TAtom::SumType(v) => {
+                write!(f, "{}", v.decl.constructor(v.variant))?;
+                for field in &v.fields {
+                    match field {
+                        TNoun::Atom(_) => write!(f, " {field}")?,
+                        TNoun::Array(_) => write!(f, " ({field})")?,
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Vectors are written on one line, strings as they are and anything else with spaces between the
+/// elements. Higher ranks are written as rows, with the columns lined up and a blank line between
+/// each matrix
+impl Display for TNoun {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let arr = match self {
+            TNoun::Atom(a) => return write!(f, "{a}"),
+            TNoun::Array(arr) => arr,
+        };
+        let chars = arr.data.iter().all(|x| matches!(x, TNoun::Atom(TAtom::Char(_))));
+        let cells = arr.data.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+        let columns = *arr.shape.last().expect("arrays have rank 1 or more") as usize;
+        if columns == 0 {
+            return Ok(());
+        }
+        let mut widths = vec![0; columns];
+        for (i, cell) in cells.iter().enumerate() {
+            widths[i % columns] = widths[i % columns].max(cell.chars().count());
+        }
+        // How many elements each matrix has
+        let matrix = columns * arr.shape.iter().rev().nth(1).map_or(1, |&r| r as usize);
+        for (i, cell) in cells.iter().enumerate() {
+            if i > 0 && i % columns == 0 {
+                writeln!(f)?;
+                if i % matrix == 0 {
+                    writeln!(f)?;
+                }
+            } else if i > 0 && !chars {
+                write!(f, " ")?;
+            }
+            match chars || arr.shape.len() == 1 {
+                true => write!(f, "{cell}")?,
+                false => write!(f, "{cell:>0$}", widths[i % columns])?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl TArray {
+    /// Scalars are kept as [`TNoun::Atom`]s, never as rank 0 arrays
+    pub fn noun(shape: Shape, data: Vec<TNoun>) -> TNoun {
+        debug_assert_eq!(shape.iter().product::<u32>() as usize, data.len());
+        if shape.is_empty() {
+            data.into_iter().next().expect("rank 0 arrays have one element")
+        } else {
+            TNoun::Array(Self { shape, data })
+        }
+    }
+    pub fn vector(data: Vec<TNoun>) -> TNoun {
+        TNoun::Array(Self {
+            shape: vec![data.len() as u32],
+            data,
+        })
     }
 }
@@ -1,4 +1,139 @@
+//! The `tqo` command:
+//!
+//! - `tqo run FILE ARGS...` type checks FILE, reporting every error in it, and runs it if there
+//!   are none, printing the value of its last statement. `tqo FILE` is short for it
+//! - `tqo check FILE` only type checks it
+//! - `tqo -e EXPR ARGS...` runs EXPR as though it were a file
+//! - `tqo repl ARGS...`, or just `tqo`, starts a repl, see [`repl`]
+//! - `tqo fmt --names FILE` prints FILE with its glyphs spelled out as `\name`s, and
+//!   `tqo fmt --glyphs FILE` turns them back, see [`tqo::glyphs`]
+//! - `tqo docs` prints the README's reference of the primitives, see [`tqo::glyphs::docs`]
+//!
+//! The ARGS are given to the program as `⎕args`. Errors are written to stderr for people to read
+//! or, with `--json` before the command, to stdout as one JSON object per line, see
+//! [`Diagnostic::json`]. Either way the exit status is 1 if there were any. A `⍣` gives up after
+//! [`REPEAT_LIMIT`] iterations, or as many as `--repeat-limit N` before the command says. With
+//! `--strict`, functions bound without a `:` signature are errors, which suggest one to paste in
 
-fn main() {
-    
+use std::{env, fs, process::ExitCode};
+
+use tqo::{Diagnostic, Evaluator, TError};
+
+mod repl;
+
+const USAGE: &str = "usage: tqo [--json] [--strict] [--repeat-limit N] run FILE [ARGS...]
+       tqo [--json] [--strict] check FILE
+       tqo [--json] [--strict] [--repeat-limit N] -e EXPR [ARGS...]
+       tqo [--repeat-limit N] repl [ARGS...]
+       tqo fmt --glyphs|--names FILE
+       tqo docs";
+
+/// How many times a `⍣` without a count may iterate, so that one whose condition never holds
+/// stops with an error rather than hanging
+const REPEAT_LIMIT: usize = 1_000_000;
+
+enum Command {
+    Run,
+    Check,
+}
+
+fn main() -> ExitCode {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let (mut json, mut strict, mut limit, mut args) = (false, false, REPEAT_LIMIT, &args[..]);
+    loop {
+        match args {
+            [flag, rest @ ..] if flag == "--json" => (json, args) = (true, rest),
+            [flag, rest @ ..] if flag == "--strict" => (strict, args) = (true, rest),
+            [flag, n, rest @ ..] if flag == "--repeat-limit" => match n.parse() {
+                Ok(n) => (limit, args) = (n, rest),
+                Err(_) => {
+                    eprintln!("{USAGE}");
+                    return ExitCode::FAILURE;
+                }
+            },
+            _ => break,
+        }
+    }
+    match args {
+        [] => return repl::repl(&[], limit),
+        [repl, args @ ..] if repl == "repl" && !json && !strict => return repl::repl(args, limit),
+        [command, spelling, file] if command == "fmt" && !json => return fmt(spelling, file),
+        [docs] if docs == "docs" && !json => {
+            print!("{}", tqo::glyphs::docs());
+            return ExitCode::SUCCESS;
+        }
+        _ => {}
+    }
+    let (command, file, src, args) = match args {
+        [run, file, args @ ..] if run == "run" => (Command::Run, Some(file.clone()), None, args),
+        [check, file] if check == "check" => (Command::Check, Some(file.clone()), None, &[][..]),
+        [e, expr, args @ ..] if e == "-e" => (Command::Run, None, Some(expr.clone()), args),
+        [file, args @ ..] if !file.starts_with('-') && !matches!(file.as_str(), "run" | "check" | "repl" | "fmt" | "docs") => {
+            (Command::Run, Some(file.clone()), None, args)
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let src = match (src, &file) {
+        (Some(src), _) => src,
+        (None, Some(file)) => match fs::read_to_string(file) {
+            Ok(src) => src,
+            Err(e) => {
+                eprintln!("tqo: can't read {file}: {e}");
+                return ExitCode::FAILURE;
+            }
+        },
+        (None, None) => unreachable!("either a file or an expression is given"),
+    };
+
+    let evaluator = || {
+        let e = Evaluator::new().with_args(args).with_repeat_limit(limit);
+        match strict {
+            true => e.with_signatures_required(),
+            false => e,
+        }
+    };
+    let mut diagnostics: Vec<Diagnostic> = vec![];
+    if let Err(errors) = evaluator().check_all(&src) {
+        diagnostics.extend(errors.iter().map(TError::diagnostic));
+    } else if let Command::Run = command {
+        match evaluator().run(&src) {
+            Ok(Some(value)) => println!("{value}"),
+            Ok(None) => {}
+            Err(e) => diagnostics.push(e.diagnostic()),
+        }
+    }
+    for d in &diagnostics {
+        match json {
+            true => println!("{}", d.json(&src, file.as_deref())),
+            false => eprint!("{}", d.render(&src, file.as_deref())),
+        }
+    }
+    match diagnostics.is_empty() {
+        true => ExitCode::SUCCESS,
+        false => ExitCode::FAILURE,
+    }
+}
+
+fn fmt(spelling: &str, file: &str) -> ExitCode {
+    let respell = match spelling {
+        "--glyphs" => tqo::glyphs::with_glyphs,
+        "--names" => tqo::glyphs::with_names,
+        _ => {
+            eprintln!("{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+    match fs::read_to_string(file) {
+        Ok(src) => {
+            print!("{}", respell(&src));
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("tqo: can't read {file}: {e}");
+            ExitCode::FAILURE
+        }
+    }
 }
@@ -1,4 +1,5 @@
 pub(crate) use crate::parsing::*;
+use crate::{data::DataSyntax, signature::SignatureSyntax};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ExprToken<'src> {
@@ -18,8 +19,29 @@ pub enum ExprTokenKind<'src> {
     AVerbName,   OVerbName,   DVerbName,
     // AAdverbName,  <-- CANNOT EXIST! There's no way to construct a primitive of this kind
     OAdverbName, DAdverbName,
-    ArrayAssign, VerbAssign,  AdverbAssign,
+    Jot,
+    Arrow,
     Parenthesized(Vec<ExprToken<'src>>),
+    /// `⦅...⦆`
+    Train(Vec<ExprToken<'src>>),
+    /// `a_b_c`
+    Strand(Vec<ExprToken<'src>>),
+    /// `[a ⋄ b ⋄ c]`, each item being a statement. A pattern when right before an [`ExprTokenKind::Arrow`]
+    Bracket(Vec<Vec<ExprToken<'src>>>),
+    /// `.name`, which gets a field out of a value of a user-defined type
+    Field,
+    /// `⊔⟨ pattern → result ⋄ ... ⟩`, each arm being a statement
+    Match(Vec<Vec<ExprToken<'src>>>),
+    /// `→`, between the pattern and the result of an arm
+    Then,
+    /// `_`, which matches anything
+    Wildcard,
+    /// `□ Name ← ⟨ ... ⟩`, always a statement of its own
+    Data(Box<DataSyntax>),
+    /// `: ∀T 'T » Name ↳ 'T`, always a statement of its own
+    Signature(Box<SignatureSyntax>),
+    /// Its statements, and the kind of verb it is given which arguments it uses
+    Dfn { body: Vec<Vec<ExprToken<'src>>>, cat: Category, depth: usize },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -41,7 +63,7 @@ pub(crate) enum Category {
     Oa,
     /// Dyadic adverb
     Da,
-    /// ...should this be here?
+    /// `∘`, which binds arguments to verbs and composes them
     Jot,
     /// Arrow (in-line assignment exists)
     Arr,
@@ -68,135 +90,270 @@ const _CHECK_CATEGORY_DISCRIMINANTS: () = {
         )
     }
 };
-pub(crate) fn binding_power_of<'src>(
-    a: &ExprTree<'src>,
-    b: &ExprTree<'src>,
-) -> Option<(u8, ExprTree<'src>)> {
-    use Category::*;
-    let (a, b) = (a.clone(), b.clone());
+
+/// How strongly two adjacent trees want to be merged by [`merge`]. Zero means they can't be
+pub(crate) fn binding_power_of(a: &ExprTree, b: &ExprTree) -> u8 {
     let (ac, bc) = (a.category(), b.category());
     assert!((ac as u8) < 11 && (bc as u8) < 11);
-    let s = |bp, c| Some((bp, c));
-    let tbd = None; // for now!
-
-    fn merge_with_cat<'src>(
-        cat: Category,
-        left: ExprTree<'src>,
-        right: ExprTree<'src>,
-    ) -> ExprTree<'src> {
-        match cat {
-            Category::A | Category::N => unreachable!(),
-            Category::Av => ExprTree::AlphaVerbCall {
-                alpha: Box::new(left),
-                verb: Box::new(right),
-            },
-            Category::Ov => ExprTree::OmegaVerbCall {
-                verb: Box::new(left),
-                omega: Box::new(right),
-            },
-            Category::Dv => unreachable!("i am honestly not really sure"),
-            Category::Aa => ExprTree::AlphaAdverbCall {
-                alpha: Box::new(left),
-                adverb: Box::new(right),
-            },
-            Category::Oa => ExprTree::OmegaAdverbCall {
-                adverb: Box::new(left),
-                omega: Box::new(right),
-            },
-            Category::Da => todo!(),
-            Category::Jot => unreachable!(),
-            Category::Arr => unreachable!(),
-            Category::Ass => todo!(),
-        }
-    }
+    // Assignment, application, operands, names and jots
+    const __: u8 = 0;
+    const AS: u8 = 1;
+    const AP: u8 = 2;
+    const OP: u8 = 3;
+    const NM: u8 = 4;
+    const JT: u8 = 5;
 
+    // What's left of a jot becomes its left operand, as with dyadic adverbs
     #[rustfmt::skip]
-    let table: [[Option<_>; 11]; 11] = [
-              /* A             αV            ⍵V            DV           N             αA            ⍵A           DA            JOT          ARR            ASS*/
-        /*A*/   [None,         tbd.clone(),  None,         s(2, Ov),    None, tbd.clone(), tbd.clone(),  tbd.clone(),  tbd.clone(),  tbd.clone(), tbd.clone()],
-        /*αV*/  [None,         tbd.clone(),  None,         tbd.clone(), None, tbd.clone(), tbd.clone(),  tbd.clone(),  tbd.clone(),  tbd.clone(), tbd.clone()],
-        /*⍵V*/  [tbd.clone(),  None,         tbd.clone(),  None,        tbd.clone(),  tbd.clone(), None, None, None, tbd.clone(), tbd.clone()],
-        /*DV*/  [tbd.clone(),  None,         tbd.clone(),  None,        tbd.clone(),  tbd.clone(), None, None, None, tbd.clone(), tbd.clone()],
-        /*N*/   [None,         tbd.clone(),  None,         tbd.clone(), None, tbd.clone(), tbd.clone(),  tbd.clone(),  tbd.clone(),  tbd.clone(), tbd.clone()],
-        /*αA*/  [None,         tbd.clone(),  tbd.clone(),  tbd.clone(), tbd.clone(),  tbd.clone(), tbd.clone(),  tbd.clone(),  tbd.clone(),  tbd.clone(), tbd.clone()],
-        /*⍵A*/  [tbd.clone(),  tbd.clone(),  tbd.clone(),  tbd.clone(), tbd.clone(),  tbd.clone(), tbd.clone(),  tbd.clone(),  tbd.clone(),  tbd.clone(), tbd.clone()],
-        /*DA*/  [tbd.clone(),  tbd.clone(),  tbd.clone(),  tbd.clone(), tbd.clone(),  tbd.clone(), tbd.clone(),  tbd.clone(),  tbd.clone(),  tbd.clone(), tbd.clone()],
-        /*JOT*/ [tbd.clone(),  tbd.clone(),  tbd.clone(),  tbd.clone(), tbd.clone(),  tbd.clone(), tbd.clone(),  tbd.clone(),  tbd.clone(),  tbd.clone(), tbd.clone()],
-        /*ARR*/ [tbd.clone(),  tbd.clone(),  tbd.clone(),  tbd.clone(), tbd.clone(),  tbd.clone(), tbd.clone(),  tbd.clone(),  tbd.clone(),  tbd.clone(), tbd.clone()],
-        /*ASS*/ [tbd.clone(),  tbd.clone(),  tbd.clone(),  tbd.clone(), tbd.clone(),  tbd.clone(), tbd.clone(),  tbd.clone(),  tbd.clone(),  tbd.clone(), tbd.clone()],
+    let table: [[u8; 11]; 11] = [
+              /* A   αV  ⍵V  DV  N   αA  ⍵A  DA  JOT ARR ASS */
+        /*A*/   [__, AP, __, AP, __, OP, __, OP, JT, __, __],
+        /*αV*/  [__, __, __, __, __, OP, __, OP, JT, __, __],
+        /*⍵V*/  [AP, __, __, __, AP, OP, __, OP, JT, __, __],
+        /*DV*/  [AP, __, __, __, AP, OP, __, OP, JT, __, __],
+        /*N*/   [__, AP, __, AP, __, OP, __, OP, JT, NM, __],
+        /*αA*/  [__, __, __, __, __, __, __, __, __, __, __],
+        /*⍵A*/  [OP, OP, OP, OP, OP, __, __, __, __, __, __],
+        /*DA*/  [OP, OP, OP, OP, OP, __, __, __, __, __, __],
+        /*JOT*/ [__, __, __, __, __, __, __, __, __, __, __],
+        /*ARR*/ [__, __, __, __, __, __, __, __, __, __, __],
+        /*ASS*/ [AS, AS, AS, AS, AS, __, __, __, __, __, __],
     ];
 
-    match table[ac as u8 as usize][bc as u8 as usize] {
-        Some((bp, c)) => Some((bp, merge_with_cat(c, a, b))),
-        None => None,
+    table[ac as u8 as usize][bc as u8 as usize]
+}
+
+/// Builds the tree for two trees that bind (see [`binding_power_of`])
+fn merge<'src>(src: &str, left: ExprTree<'src>, right: ExprTree<'src>) -> ExprTree<'src> {
+    use Category::*;
+    let b = Box::new;
+    match (left.category(), right.category()) {
+        (Ass, _) => match left {
+            ExprTree::Leaf {
+                t: ExprToken {
+                    kind: ExprTokenKind::Bracket(items),
+                    span,
+                },
+                ..
+            } => ExprTree::Destructure {
+                names: items.iter().map(|item| item[0].span.slice(src).to_string()).collect(),
+                pattern: span,
+                val: b(right),
+            },
+            _ => ExprTree::Assignment {
+                name: left.span().slice(src).to_string(),
+                val: b(right),
+            },
+        },
+        (N, Arr) => match left {
+            ExprTree::Leaf { t, .. } => ExprTree::Leaf { cat: Ass, t },
+            _ => unreachable!("only leaves are names"),
+        },
+        (A | N, Av) => match right {
+            ExprTree::OmegaBound { verb, omega } => ExprTree::DyadicVerbCall {
+                verb,
+                alpha: b(left),
+                omega,
+            },
+            verb => ExprTree::AlphaVerbCall {
+                alpha: b(left),
+                verb: b(verb),
+            },
+        },
+        (A | N, Dv) => ExprTree::AlphaBound {
+            alpha: b(left),
+            verb: b(right),
+        },
+        (Ov, A | N) => match left {
+            ExprTree::AlphaBound { alpha, verb } => ExprTree::DyadicVerbCall {
+                verb,
+                alpha,
+                omega: b(right),
+            },
+            verb => ExprTree::OmegaVerbCall {
+                verb: b(verb),
+                omega: b(right),
+            },
+        },
+        (Dv, A | N) => ExprTree::OmegaBound {
+            verb: b(left),
+            omega: b(right),
+        },
+        (_, Aa) => match right {
+            ExprTree::OmegaOperand { adverb, omega } => ExprTree::DyadicAdverbCall {
+                cat: derived_category(src, &adverb, Some(left.category()), Some(omega.category())),
+                adverb,
+                alpha: b(left),
+                omega,
+            },
+            adverb => ExprTree::AlphaAdverbCall {
+                cat: derived_category(src, &adverb, Some(left.category()), None),
+                adverb: b(adverb),
+                alpha: b(left),
+            },
+        },
+        (_, Da | Jot) => ExprTree::AlphaOperand {
+            alpha: b(left),
+            adverb: b(right),
+        },
+        (Oa, _) => match left {
+            ExprTree::AlphaOperand { alpha, adverb } => ExprTree::DyadicAdverbCall {
+                cat: derived_category(src, &adverb, Some(alpha.category()), Some(right.category())),
+                adverb,
+                alpha,
+                omega: b(right),
+            },
+            adverb => ExprTree::OmegaAdverbCall {
+                cat: derived_category(src, &adverb, None, Some(right.category())),
+                adverb: b(adverb),
+                omega: b(right),
+            },
+        },
+        (Da, _) => ExprTree::OmegaOperand {
+            adverb: b(left),
+            omega: b(right),
+        },
+        (l, r) => unreachable!("{l:?} and {r:?} do not bind"),
     }
 }
 
-fn parse_expr_go<'src>(
-    mut ts: Vec<ExprTree<'src>>,
-    start: Sp<'src>,
-) -> TResult<'src, ExprTree<'src>> {
-    use TParseErrKind as EK;
+/// What kind of verb the modifier `adverb` makes out of operands of the given categories
+fn derived_category(src: &str, adverb: &ExprTree, alpha: Option<Category>, omega: Option<Category>) -> Category {
+    match adverb {
+        ExprTree::Leaf {
+            t: ExprToken {
+                kind:
+                    ExprTokenKind::PrimAAdverb
+                    | ExprTokenKind::PrimOAdverb
+                    | ExprTokenKind::PrimDAdverb
+                    | ExprTokenKind::Jot,
+                span,
+            },
+            ..
+        } => {
+            let glyph = span.slice(src).chars().next().expect("tokens are non-empty");
+            Primitive::from_glyph(glyph)
+                .expect("lexer only emits known primitives")
+                .derived_category(alpha, omega)
+        }
+        // User-defined modifiers take after their verb operand
+        _ => omega.or(alpha).filter(|c| matches!(c, Category::Av | Category::Ov | Category::Dv)).unwrap_or(Category::Av),
+    }
+}
+
+fn parse_expr_go<'src>(src: &str, ts: Vec<ExprTree<'src>>) -> TResult<'src, ExprTree<'src>> {
+    let mut ts = reduce(src, ts);
+    if ts.len() == 1 {
+        return Ok(ts.pop().expect("just checked the length"));
+    }
+    // Nothing left to reduce, so some pair must not bind
+    let i = (0..ts.len() - 1)
+        .find(|&i| binding_power_of(&ts[i], &ts[i + 1]) == 0)
+        .expect("every pair binds but nothing was reduced");
+    Err(TParseErr::with_span(ts[i].span().merge(ts[i + 1].span()), TParseErrKind::CannotBind).into())
+}
 
-    // NOTE: `cur` always points to the (would-be) op
+/// Merges trees for as long as any of them bind, giving back what's left
+fn reduce<'src>(src: &str, mut ts: Vec<ExprTree<'src>>) -> Vec<ExprTree<'src>> {
+    // NOTE: `marker` always points to the (would-be) op
     let mut marker = 0;
     loop {
-        dbg!(marker);
-        if ts.len() == 1 {
-            return Ok(ts[0].clone());
+        if ts.len() == 1 || marker >= ts.len() {
+            return ts;
         }
-        let current = match ts.get(marker) {
-            Some(t) => t,
-            None => {
-                return Err(TParseErr::with_span(
-                    ts.last().cloned().map(|t| t.span()).unwrap_or(start),
-                    EK::UnexpectedEndOfExpression,
-                ))?;
-            }
-        };
 
-        let (l_bp, l_ret) = if marker == 0 {
-            (0, None)
-        } else {
-            match ts.get(marker - 1) {
-                Some(l_ret) => match binding_power_of(l_ret, current) {
-                    None => (0, None),
-                    Some((bp, l_ret)) => (bp, Some(l_ret)),
-                },
-                None => (0, None),
-            }
+        let l_bp = match marker {
+            0 => 0,
+            _ => binding_power_of(&ts[marker - 1], &ts[marker]),
         };
-        let (r_bp, r_ret) = match dbg!(ts.get(marker + 1)) {
-            Some(r_tok) => match binding_power_of(current, r_tok) {
-                None => (0, None),
-                Some((bp, r_ret)) => (bp, Some(r_ret)),
-            },
-            None => (0, None),
+        let r_bp = match ts.get(marker + 1) {
+            Some(r) => binding_power_of(&ts[marker], r),
+            None => 0,
         };
 
-        dbg!(l_bp, r_bp);
-
-        // Translated talqual from the bundagerth paper
-        if l_bp < r_bp {
+        // Translated talqual from the bundagerth paper, ties go to the left as we're LTR
+        if l_bp > 0 && l_bp >= r_bp {
+            let right = ts.remove(marker);
+            let left = ts.remove(marker - 1);
+            ts.insert(marker - 1, merge(src, left, right));
+            marker -= 1;
+        } else {
             marker += 1;
-        } else if l_bp == r_bp {
-            if l_bp == 0 {
-                todo!("SYNTAX ERROR")
-            } else {
-                marker += 1;
-            }
-        } else if l_bp > r_bp {
-            // reduce current with left and repeat
-            let r_ret = r_ret.unwrap().clone();
-            ts.remove(marker);
-            ts.remove(marker - 1);
-            ts.insert(marker, r_ret);
         }
     }
 }
 
 type Tree<'src> = Box<ExprTree<'src>>;
 
+/// What an arm of `⊔` matches
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Pattern<'src> {
+    /// `_`
+    Wildcard,
+    /// A name on its own, which is either a variant without fields of the matched value's type
+    /// or binds the value
+    Name(String),
+    /// A number, char or string, matching values that match it
+    Literal(ExprToken<'src>),
+    /// `Variant a b`. Either every field or none of them is given
+    Variant { name: String, fields: Vec<Pattern<'src>> },
+    /// `[a ⋄ b]`, matching each major cell
+    Array(Vec<Pattern<'src>>),
+}
+
+impl<'src> Pattern<'src> {
+    fn parse(src: &str, ts: &[ExprToken<'src>]) -> TResult<'src, Self> {
+        let name = |t: &ExprToken| match t.kind {
+            ExprTokenKind::ArrayName
+            | ExprTokenKind::AVerbName
+            | ExprTokenKind::OVerbName
+            | ExprTokenKind::DVerbName => Some(t.span.slice(src).to_string()),
+            _ => None,
+        };
+        match ts {
+            [t] => Self::single(src, t),
+            [first, fields @ ..] if let Some(name) = name(first) => Ok(Pattern::Variant {
+                name,
+                fields: fields.iter().map(|t| Self::single(src, t)).collect::<Result<_, _>>()?,
+            }),
+            _ => {
+                let span = ts.first().map_or(Sp::ZERO, |t| t.span.merge(ts[ts.len() - 1].span));
+                Err(TParseErr::with_span(span, TParseErrKind::BadPattern).into())
+            }
+        }
+    }
+    fn single(src: &str, t: &ExprToken<'src>) -> TResult<'src, Self> {
+        Ok(match &t.kind {
+            ExprTokenKind::Wildcard => Pattern::Wildcard,
+            ExprTokenKind::ArrayName
+            | ExprTokenKind::AVerbName
+            | ExprTokenKind::OVerbName
+            | ExprTokenKind::DVerbName => Pattern::Name(t.span.slice(src).to_string()),
+            ExprTokenKind::Number | ExprTokenKind::Char | ExprTokenKind::String => Pattern::Literal(t.clone()),
+            ExprTokenKind::Parenthesized(inner) => Self::parse(src, inner)?,
+            ExprTokenKind::Bracket(items) => Pattern::Array(
+                items
+                    .iter()
+                    .map(|item| Self::parse(src, item))
+                    .collect::<Result<_, _>>()?,
+            ),
+            _ => return Err(TParseErr::with_span(t.span, TParseErrKind::BadPattern).into()),
+        })
+    }
+    pub(crate) fn to_static(&self) -> Pattern<'static> {
+        match self {
+            Pattern::Wildcard => Pattern::Wildcard,
+            Pattern::Name(name) => Pattern::Name(name.clone()),
+            Pattern::Literal(t) => Pattern::Literal(t.to_static()),
+            Pattern::Variant { name, fields } => Pattern::Variant {
+                name: name.clone(),
+                fields: fields.iter().map(Self::to_static).collect(),
+            },
+            Pattern::Array(items) => Pattern::Array(items.iter().map(Self::to_static).collect()),
+        }
+    }
+}
+
 /// Used to parse expressions, not top-level elements
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) enum ExprTree<'src> {
@@ -217,47 +374,133 @@ pub(crate) enum ExprTree<'src> {
         alpha: Tree<'src>,
         omega: Tree<'src>,
     },
+    /// A dyadic verb with only its alpha, e.g. `1+`
+    AlphaBound {
+        alpha: Tree<'src>,
+        verb: Tree<'src>,
+    },
+    /// A dyadic verb with only its omega, e.g. `+1`
+    OmegaBound {
+        verb: Tree<'src>,
+        omega: Tree<'src>,
+    },
     Assignment {
         name: String,
         val: Tree<'src>,
     },
+    /// `[a ⋄ b] ← val`, which assigns each major cell of `val` to a name
+    Destructure {
+        names: Vec<String>,
+        pattern: Sp<'src>,
+        val: Tree<'src>,
+    },
     AlphaAdverbCall {
         adverb: Tree<'src>,
         alpha: Tree<'src>,
+        /// Of the derived verb
+        cat: Category,
     },
     OmegaAdverbCall {
         adverb: Tree<'src>,
         omega: Tree<'src>,
+        /// Of the derived verb
+        cat: Category,
     },
     DyadicAdverbCall {
         adverb: Tree<'src>,
         alpha: Tree<'src>,
         omega: Tree<'src>,
+        /// Of the derived verb
+        cat: Category,
+    },
+    /// A dyadic adverb with only its left operand, e.g. `3⍣`
+    AlphaOperand {
+        alpha: Tree<'src>,
+        adverb: Tree<'src>,
+    },
+    /// A dyadic adverb with only its right operand, e.g. `⍣⇌`
+    OmegaOperand {
+        adverb: Tree<'src>,
+        omega: Tree<'src>,
+    },
+    Dfn {
+        body: Vec<ExprTree<'src>>,
+        cat: Category,
+        /// Which of [`ARGUMENTS`] it takes
+        depth: usize,
+        span: Sp<'src>,
+    },
+    /// `[a ⋄ b ⋄ c]`, with its items as major cells
+    Bracket {
+        items: Vec<ExprTree<'src>>,
+        span: Sp<'src>,
+    },
+    /// A vector of its items, e.g. `1_2_3`. Its length is known without evaluating them
+    Strand {
+        items: Vec<ExprTree<'src>>,
+        span: Sp<'src>,
+    },
+    /// `⊔⟨ ... ⟩`, an alpha-monadic verb giving the result of the first arm whose pattern matches
+    Match {
+        arms: Vec<(Pattern<'src>, ExprTree<'src>)>,
+        span: Sp<'src>,
+    },
+    /// Either a fork, `⦅f g h⦆`, or an atop, `⦅f g⦆`
+    Train {
+        tines: Vec<ExprTree<'src>>,
+        cat: Category,
+        span: Sp<'src>,
     },
 }
 
 /// `ts` must be non-empty
 pub(crate) fn parse_expr<'src>(
-    ts: &'src [ExprToken],
+    src: &str,
+    ts: &[ExprToken<'src>],
     start_span: Sp<'src>,
 ) -> TResult<'src, ExprTree<'src>> {
     if ts.is_empty() {
-        return Err(TError {
-            span: start_span,
-            kind: TErrorKind::EmptyExpr,
-        });
+        return Err(TError::new(TErrorKind::EmptyExpr, start_span));
+    }
+    let trees = ts.iter().enumerate().map(|(i, t)| match ts.get(i + 1) {
+        Some(ExprToken {
+            kind: ExprTokenKind::Arrow,
+            ..
+        }) => t.to_target(),
+        _ => t.to_tree(src),
+    });
+    let tree = parse_expr_go(src, trees.collect::<Result<_, _>>()?)?;
+    match tree.category() {
+        Category::A | Category::N | Category::Av | Category::Ov | Category::Dv => Ok(tree),
+        _ => Err(TParseErr::with_span(tree.span(), TParseErrKind::Incomplete))?,
     }
-    parse_expr_go(ts.iter().map(|t| t.to_tree()).collect(), start_span)
 }
 
 impl<'src> ExprToken<'src> {
-    fn to_tree(&self) -> ExprTree {
+    /// What's being assigned to. A bracket of names here is a pattern, not an array
+    fn to_target(&self) -> TResult<'src, ExprTree<'src>> {
+        match &self.kind {
+            ExprTokenKind::ArrayName => {}
+            ExprTokenKind::Bracket(items) => {
+                if let Some(item) = items.iter().find(|item| !matches!(&item[..], [ExprToken { kind: ExprTokenKind::ArrayName, .. }])) {
+                    let span = item[0].span.merge(item[item.len() - 1].span);
+                    return Err(TParseErr::with_span(span, TParseErrKind::BadPattern).into());
+                }
+            }
+            _ => return Err(TParseErr::with_span(self.span, TParseErrKind::BadPattern).into()),
+        }
+        Ok(ExprTree::Leaf {
+            cat: Category::N,
+            t: self.clone(),
+        })
+    }
+    fn to_tree(&self, src: &str) -> TResult<'src, ExprTree<'src>> {
         let leaf = |cat| ExprTree::Leaf {
             cat,
             t: self.clone(),
         };
 
-        match &self.kind {
+        Ok(match &self.kind {
             ExprTokenKind::Number => leaf(Category::A),
             ExprTokenKind::String => leaf(Category::A),
             ExprTokenKind::Char => leaf(Category::A),
@@ -270,45 +513,261 @@ impl<'src> ExprToken<'src> {
             ExprTokenKind::PrimOAdverb => leaf(Category::Oa),
             ExprTokenKind::ArrayName => leaf(Category::N),
             ExprTokenKind::AVerbName => leaf(Category::Av),
-            ExprTokenKind::OVerbName => leaf(Category::Av),
+            ExprTokenKind::OVerbName => leaf(Category::Ov),
             ExprTokenKind::OAdverbName => leaf(Category::Oa),
-            ExprTokenKind::ArrayAssign => leaf(Category::Ass),
-            ExprTokenKind::VerbAssign => leaf(Category::Ass),
-            ExprTokenKind::AdverbAssign => leaf(Category::Ass),
+            ExprTokenKind::Arrow => leaf(Category::Arr),
             ExprTokenKind::DVerbName => leaf(Category::Dv),
             ExprTokenKind::DAdverbName => leaf(Category::Da),
-            ExprTokenKind::Parenthesized(tokens) => todo!(),
+            ExprTokenKind::Jot => leaf(Category::Jot),
+            ExprTokenKind::Field => leaf(Category::Av),
+            ExprTokenKind::Match(arms) => ExprTree::Match {
+                arms: arms
+                    .iter()
+                    .map(|arm| {
+                        let span = arm[0].span.merge(arm[arm.len() - 1].span);
+                        let bad = || TError::from(TParseErr::with_span(span, TParseErrKind::BadMatch));
+                        let then = arm.iter().position(|t| t.kind == ExprTokenKind::Then).ok_or_else(bad)?;
+                        if then == 0 || then == arm.len() - 1 {
+                            return Err(bad());
+                        }
+                        let pattern = Pattern::parse(src, &arm[..then])?;
+                        Ok((pattern, parse_expr(src, &arm[then + 1..], arm[then].span)?))
+                    })
+                    .collect::<Result<_, _>>()?,
+                span: self.span,
+            },
+            ExprTokenKind::Then => return Err(TParseErr::with_span(self.span, TParseErrKind::BadMatch).into()),
+            ExprTokenKind::Wildcard => return Err(TParseErr::with_span(self.span, TParseErrKind::BadPattern).into()),
+            ExprTokenKind::Data(_) | ExprTokenKind::Signature(_) => unreachable!("declarations are not expressions"),
+            ExprTokenKind::Parenthesized(tokens) => parse_expr(src, tokens, self.span)?,
+            ExprTokenKind::Dfn { body, cat, depth } => ExprTree::Dfn {
+                body: body
+                    .iter()
+                    .map(|statement| parse_expr(src, statement, statement[0].span))
+                    .collect::<Result<_, _>>()?,
+                cat: *cat,
+                depth: *depth,
+                span: self.span,
+            },
+            ExprTokenKind::Bracket(items) => ExprTree::Bracket {
+                items: items
+                    .iter()
+                    .map(|item| parse_expr(src, item, item[0].span))
+                    .collect::<Result<_, _>>()?,
+                span: self.span,
+            },
+            ExprTokenKind::Strand(tokens) => {
+                let items: Vec<_> = tokens.iter().map(|t| t.to_tree(src)).collect::<Result<_, _>>()?;
+                if let Some(item) = items.iter().find(|t| !matches!(t.category(), Category::A | Category::N)) {
+                    return Err(TParseErr::with_span(item.span(), TParseErrKind::BadStrand).into());
+                }
+                ExprTree::Strand { items, span: self.span }
+            }
+            ExprTokenKind::Train(tokens) => {
+                let tines = reduce(src, tokens.iter().map(|t| t.to_tree(src)).collect::<Result<_, _>>()?);
+                let cats: Vec<_> = tines.iter().map(ExprTree::category).collect();
+                let verb = |c: &Category| matches!(c, Category::Av | Category::Ov | Category::Dv);
+                let monadic = |c: &Category| matches!(c, Category::Av | Category::Ov);
+                let cat = match &cats[..] {
+                    // Atop, the second tine is applied to what the first gives
+                    [f, Category::Av | Category::Ov] if verb(f) => *f,
+                    // Fork, the middle tine is applied between what the outer ones give. Monadic
+                    // ones may take their argument on different sides, and the fork takes it on
+                    // the first one's
+                    [f, Category::Dv, h] if verb(f) && f == h || monadic(f) && monadic(h) => *f,
+                    _ => return Err(TParseErr::with_span(self.span, TParseErrKind::BadTrain).into()),
+                };
+                ExprTree::Train {
+                    tines,
+                    cat,
+                    span: self.span,
+                }
+            }
+        })
+    }
+    fn to_static(&self) -> ExprToken<'static> {
+        let kind = match &self.kind {
+            ExprTokenKind::Parenthesized(ts) => ExprTokenKind::Parenthesized(ts.iter().map(Self::to_static).collect()),
+            ExprTokenKind::Train(ts) => ExprTokenKind::Train(ts.iter().map(Self::to_static).collect()),
+            ExprTokenKind::Strand(ts) => ExprTokenKind::Strand(ts.iter().map(Self::to_static).collect()),
+            ExprTokenKind::Bracket(items) => {
+                ExprTokenKind::Bracket(items.iter().map(|item| item.iter().map(Self::to_static).collect()).collect())
+            }
+            ExprTokenKind::Dfn { body, cat, depth } => ExprTokenKind::Dfn {
+                body: body.iter().map(|st| st.iter().map(Self::to_static).collect()).collect(),
+                cat: *cat,
+                depth: *depth,
+            },
+            ExprTokenKind::Number => ExprTokenKind::Number,
+            ExprTokenKind::String => ExprTokenKind::String,
+            ExprTokenKind::Char => ExprTokenKind::Char,
+            ExprTokenKind::PrimArray => ExprTokenKind::PrimArray,
+            ExprTokenKind::ArrayName => ExprTokenKind::ArrayName,
+            ExprTokenKind::PrimAVerb => ExprTokenKind::PrimAVerb,
+            ExprTokenKind::PrimOVerb => ExprTokenKind::PrimOVerb,
+            ExprTokenKind::PrimDVerb => ExprTokenKind::PrimDVerb,
+            ExprTokenKind::PrimAAdverb => ExprTokenKind::PrimAAdverb,
+            ExprTokenKind::PrimOAdverb => ExprTokenKind::PrimOAdverb,
+            ExprTokenKind::PrimDAdverb => ExprTokenKind::PrimDAdverb,
+            ExprTokenKind::AVerbName => ExprTokenKind::AVerbName,
+            ExprTokenKind::OVerbName => ExprTokenKind::OVerbName,
+            ExprTokenKind::DVerbName => ExprTokenKind::DVerbName,
+            ExprTokenKind::OAdverbName => ExprTokenKind::OAdverbName,
+            ExprTokenKind::DAdverbName => ExprTokenKind::DAdverbName,
+            ExprTokenKind::Jot => ExprTokenKind::Jot,
+            ExprTokenKind::Field => ExprTokenKind::Field,
+            ExprTokenKind::Match(arms) => {
+                ExprTokenKind::Match(arms.iter().map(|arm| arm.iter().map(Self::to_static).collect()).collect())
+            }
+            ExprTokenKind::Then => ExprTokenKind::Then,
+            ExprTokenKind::Wildcard => ExprTokenKind::Wildcard,
+            ExprTokenKind::Data(d) => ExprTokenKind::Data(d.clone()),
+            ExprTokenKind::Signature(sig) => ExprTokenKind::Signature(sig.clone()),
+            ExprTokenKind::Arrow => ExprTokenKind::Arrow,
+        };
+        ExprToken {
+            kind,
+            span: self.span.to_static(),
         }
     }
 }
 
 impl<'src> ExprTree<'src> {
-    fn category(&self) -> Category {
+    pub(crate) fn category(&self) -> Category {
         match self {
-            ExprTree::Leaf { cat, t } => *cat,
-            ExprTree::AlphaVerbCall { .. } => Category::Av,
-            ExprTree::OmegaVerbCall { .. } => Category::Ov,
-            ExprTree::Assignment { .. } => Category::Ass,
-            ExprTree::AlphaAdverbCall { .. } => Category::Aa,
-            ExprTree::OmegaAdverbCall { .. } => Category::Oa,
-            ExprTree::DyadicVerbCall { .. } => Category::Dv,
-            ExprTree::DyadicAdverbCall { .. } => Category::Da,
+            ExprTree::Leaf { cat, .. } => *cat,
+            ExprTree::AlphaVerbCall { .. }
+            | ExprTree::OmegaVerbCall { .. }
+            | ExprTree::DyadicVerbCall { .. } => Category::A,
+            ExprTree::AlphaBound { .. } => Category::Ov,
+            ExprTree::OmegaBound { .. } => Category::Av,
+            ExprTree::Assignment { val, .. } => match val.category() {
+                Category::N => Category::A,
+                c => c,
+            },
+            ExprTree::AlphaAdverbCall { cat, .. }
+            | ExprTree::OmegaAdverbCall { cat, .. }
+            | ExprTree::DyadicAdverbCall { cat, .. } => *cat,
+            ExprTree::AlphaOperand { .. } => Category::Oa,
+            ExprTree::OmegaOperand { .. } => Category::Aa,
+            ExprTree::Dfn { cat, .. } | ExprTree::Train { cat, .. } => *cat,
+            ExprTree::Strand { .. } | ExprTree::Bracket { .. } | ExprTree::Destructure { .. } => Category::A,
+            ExprTree::Match { .. } => Category::Av,
+        }
+    }
+    pub(crate) fn span(&self) -> Sp<'src> {
+        match self {
+            ExprTree::Leaf { t, .. } => t.span,
+            ExprTree::AlphaVerbCall { alpha: l, verb: r }
+            | ExprTree::OmegaVerbCall { verb: l, omega: r }
+            | ExprTree::AlphaBound { alpha: l, verb: r }
+            | ExprTree::OmegaBound { verb: l, omega: r }
+            | ExprTree::AlphaAdverbCall { alpha: l, adverb: r, .. }
+            | ExprTree::OmegaAdverbCall { adverb: l, omega: r, .. }
+            | ExprTree::AlphaOperand { alpha: l, adverb: r }
+            | ExprTree::OmegaOperand { adverb: l, omega: r }
+            | ExprTree::DyadicVerbCall { alpha: l, omega: r, .. }
+            | ExprTree::DyadicAdverbCall { alpha: l, omega: r, .. } => l.span().merge(r.span()),
+            ExprTree::Assignment { val, .. } => val.span(),
+            ExprTree::Destructure { pattern, val, .. } => pattern.merge(val.span()),
+            ExprTree::Dfn { span, .. }
+            | ExprTree::Train { span, .. }
+            | ExprTree::Strand { span, .. }
+            | ExprTree::Bracket { span, .. }
+            | ExprTree::Match { span, .. } => *span,
         }
     }
-    fn span(&self) -> Sp<'src> {
+    /// Dfns outlive the source they were written in, so they keep their own copy of it along
+    /// with a tree that doesn't borrow it
+    pub(crate) fn to_static(&self) -> ExprTree<'static> {
+        let b = |t: &Tree<'src>| Box::new(t.to_static());
         match self {
-            ExprTree::Leaf { cat, t } => t.span,
-            ExprTree::AlphaVerbCall { verb, alpha } => todo!(),
-            ExprTree::OmegaVerbCall { verb, omega } => todo!(),
-            ExprTree::Assignment { name, val } => todo!(),
-            ExprTree::AlphaAdverbCall { adverb, alpha } => todo!(),
-            ExprTree::OmegaAdverbCall { adverb, omega } => todo!(),
-            ExprTree::DyadicVerbCall { verb, alpha, omega } => todo!(),
+            ExprTree::Leaf { cat, t } => ExprTree::Leaf {
+                cat: *cat,
+                t: t.to_static(),
+            },
+            ExprTree::AlphaVerbCall { alpha, verb } => ExprTree::AlphaVerbCall {
+                alpha: b(alpha),
+                verb: b(verb),
+            },
+            ExprTree::OmegaVerbCall { verb, omega } => ExprTree::OmegaVerbCall {
+                verb: b(verb),
+                omega: b(omega),
+            },
+            ExprTree::DyadicVerbCall { verb, alpha, omega } => ExprTree::DyadicVerbCall {
+                verb: b(verb),
+                alpha: b(alpha),
+                omega: b(omega),
+            },
+            ExprTree::AlphaBound { alpha, verb } => ExprTree::AlphaBound {
+                alpha: b(alpha),
+                verb: b(verb),
+            },
+            ExprTree::OmegaBound { verb, omega } => ExprTree::OmegaBound {
+                verb: b(verb),
+                omega: b(omega),
+            },
+            ExprTree::Assignment { name, val } => ExprTree::Assignment {
+                name: name.clone(),
+                val: b(val),
+            },
+            ExprTree::AlphaAdverbCall { adverb, alpha, cat } => ExprTree::AlphaAdverbCall {
+                adverb: b(adverb),
+                alpha: b(alpha),
+                cat: *cat,
+            },
+            ExprTree::OmegaAdverbCall { adverb, omega, cat } => ExprTree::OmegaAdverbCall {
+                adverb: b(adverb),
+                omega: b(omega),
+                cat: *cat,
+            },
             ExprTree::DyadicAdverbCall {
                 adverb,
                 alpha,
                 omega,
-            } => todo!(),
+                cat,
+            } => ExprTree::DyadicAdverbCall {
+                adverb: b(adverb),
+                alpha: b(alpha),
+                omega: b(omega),
+                cat: *cat,
+            },
+            ExprTree::AlphaOperand { alpha, adverb } => ExprTree::AlphaOperand {
+                alpha: b(alpha),
+                adverb: b(adverb),
+            },
+            ExprTree::OmegaOperand { adverb, omega } => ExprTree::OmegaOperand {
+                adverb: b(adverb),
+                omega: b(omega),
+            },
+            ExprTree::Dfn { body, cat, depth, span } => ExprTree::Dfn {
+                body: body.iter().map(ExprTree::to_static).collect(),
+                cat: *cat,
+                depth: *depth,
+                span: span.to_static(),
+            },
+            ExprTree::Train { tines, cat, span } => ExprTree::Train {
+                tines: tines.iter().map(ExprTree::to_static).collect(),
+                cat: *cat,
+                span: span.to_static(),
+            },
+            ExprTree::Strand { items, span } => ExprTree::Strand {
+                items: items.iter().map(ExprTree::to_static).collect(),
+                span: span.to_static(),
+            },
+            ExprTree::Match { arms, span } => ExprTree::Match {
+                arms: arms.iter().map(|(p, body)| (p.to_static(), body.to_static())).collect(),
+                span: span.to_static(),
+            },
+            ExprTree::Bracket { items, span } => ExprTree::Bracket {
+                items: items.iter().map(ExprTree::to_static).collect(),
+                span: span.to_static(),
+            },
+            ExprTree::Destructure { names, pattern, val } => ExprTree::Destructure {
+                names: names.clone(),
+                pattern: pattern.to_static(),
+                val: b(val),
+            },
         }
     }
 }
@@ -0,0 +1,786 @@
+//! Turns source code into [`ExprToken`]s. Diamonds and newlines split the input into statements
+
+use std::collections::HashMap;
+
+use crate::{
+    ast::Sp,
+    data::{DataSyntax, FieldType, VariantSyntax},
+    parsing::*,
+    signature::{Bound, SignatureSyntax, TypeSyntax},
+    Primitive, Side,
+};
+
+/// The arguments of dfns, by nesting depth. The outermost ones always get `α` and `ω`
+pub(crate) const ARGUMENTS: [(&str, &str); 5] = [("α", "ω"), ("β", "ψ"), ("γ", "χ"), ("δ", "φ"), ("ε", "υ")];
+
+struct Lexer<'src> {
+    src: &'src str,
+    /// In bytes
+    pos: usize,
+    /// How many dfns we're in
+    depth: usize,
+    /// Where errors go when carrying on past them along with what was lexed of their statement,
+    /// see [`lex_recovering`]
+    errors: Option<Vec<(TParseErr<'src>, Vec<ExprToken<'src>>)>>,
+}
+
+/// Splits `src` into statements, each of which is non-empty
+pub(crate) fn lex<'src>(src: &'src str) -> Result<Vec<Vec<ExprToken<'src>>>, TParseErr<'src>> {
+    Lexer {
+        src,
+        pos: 0,
+        depth: 0,
+        errors: None,
+    }
+    .statements(None)
+}
+
+/// Like [`lex`], but a statement with an error is left out rather than ending it all, and given
+/// back with what was lexed of it. What follows is picked up from the next line that starts a
+/// statement of its own
+#[allow(clippy::type_complexity)]
+pub(crate) fn lex_recovering<'src>(
+    src: &'src str,
+) -> (Vec<Vec<ExprToken<'src>>>, Vec<(TParseErr<'src>, Vec<ExprToken<'src>>)>) {
+    let mut lexer = Lexer {
+        src,
+        pos: 0,
+        depth: 0,
+        errors: Some(vec![]),
+    };
+    let statements = lexer.statements(None).expect("errors at the top level are recovered from");
+    (statements, lexer.errors.unwrap_or_default())
+}
+
+/// Which pair of dfn arguments `name` belongs to, counting from 1
+fn argument_depth(name: &str) -> Option<usize> {
+    ARGUMENTS.iter().position(|(a, w)| name == *a || name == *w).map(|i| i + 1)
+}
+
+/// Whether the name `name` appears anywhere in `ts`, including nested dfns
+pub(crate) fn uses(src: &str, ts: &[ExprToken], name: &str) -> bool {
+    ts.iter().any(|t| match &t.kind {
+        ExprTokenKind::ArrayName => t.span.slice(src) == name,
+        ExprTokenKind::Parenthesized(inner) | ExprTokenKind::Train(inner) | ExprTokenKind::Strand(inner) => {
+            uses(src, inner, name)
+        }
+        ExprTokenKind::Dfn { body, .. } | ExprTokenKind::Bracket(body) | ExprTokenKind::Match(body) => {
+            body.iter().any(|st| uses(src, st, name))
+        }
+        _ => false,
+    })
+}
+
+impl<'src> Lexer<'src> {
+    /// Until `closer` (which gets consumed) or the end of the input if there's none
+    fn statements(&mut self, closer: Option<char>) -> Result<Vec<Vec<ExprToken<'src>>>, TParseErr<'src>> {
+        let start = self.pos;
+        let mut statements = vec![];
+        let mut current = vec![];
+        loop {
+            self.skip_blanks();
+            match self.peek() {
+                None if closer.is_some() => {
+                    return Err(TParseErr::with_span(self.span_from(start), TParseErrKind::UnclosedDelimiter));
+                }
+                None => break,
+                Some(c) if Some(c) == closer => {
+                    self.bump();
+                    break;
+                }
+                Some('\n' | '⋄') => {
+                    self.bump();
+                    if !current.is_empty() {
+                        statements.push(std::mem::take(&mut current));
+                    }
+                }
+                Some(c) => {
+                    let at = self.pos;
+                    let token = match c {
+                        '□' if current.is_empty() && self.depth == 0 => self.data(),
+                        ':' if current.is_empty() && self.depth == 0 => self.signature(),
+                        _ => self.token(),
+                    };
+                    match token {
+                        Ok(t) => current.push(t),
+                        Err(e) if closer.is_none() && self.errors.is_some() => {
+                            self.resync(at);
+                            // What went wrong past the end of the statement is down to a bracket
+                            // left open in it
+                            let e = match e.span.start >= self.pos {
+                                true => TParseErr::with_span(Sp::new(at, self.pos - 1), TParseErrKind::UnclosedDelimiter),
+                                false => e,
+                            };
+                            let partial = std::mem::take(&mut current);
+                            self.errors.as_mut().expect("checked above").push((e, partial));
+                        }
+                        Err(e) => return Err(e),
+                    }
+                }
+            }
+        }
+        if !current.is_empty() {
+            statements.push(current);
+        }
+        Ok(statements)
+    }
+
+    /// Skips past the statement the token at `at` is in, to the next `⋄` or newline that isn't
+    /// inside any of the brackets opened since. A line that isn't indented starts a new statement
+    /// even so, as a bracket left open would otherwise take the rest of the input with it
+    fn resync(&mut self, at: usize) {
+        self.depth = 0;
+        self.pos = at;
+        let mut level = 0usize;
+        while let Some(c) = self.bump() {
+            match c {
+                '(' | '[' | '{' | '⦅' | '⟨' => level += 1,
+                ')' | ']' | '}' | '⦆' | '⟩' => level = level.saturating_sub(1),
+                // Brackets in characters and comments don't count
+                '\'' => {
+                    self.bump();
+                    if self.peek() == Some('\'') {
+                        self.bump();
+                    }
+                }
+                '⍝' => {
+                    while !matches!(self.peek(), None | Some('\n')) {
+                        if self.bump() == Some('⍝') {
+                            break;
+                        }
+                    }
+                }
+                '\n' | '⋄' if level == 0 => return,
+                '\n' => match self.peek() {
+                    Some(')' | ']' | '}' | '⦆' | '⟩') => {}
+                    Some(c) if !c.is_whitespace() => return,
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+    fn span_from(&self, start: usize) -> Sp<'src> {
+        Sp::new(start, self.pos)
+    }
+
+    /// Whitespace (except newlines) and comments, which go on until the end of the line or the next `⍝`
+    fn skip_blanks(&mut self) {
+        loop {
+            match self.peek() {
+                Some(c) if c.is_whitespace() && c != '\n' => {
+                    self.bump();
+                }
+                Some('⍝') => {
+                    self.bump();
+                    while !matches!(self.peek(), None | Some('\n')) {
+                        if self.bump() == Some('⍝') {
+                            break;
+                        }
+                    }
+                }
+                _ => return,
+            }
+        }
+    }
+
+    /// The tokens up to `closer`, which may span several lines
+    fn bracketed(&mut self, start: usize, closer: char) -> Result<Vec<ExprToken<'src>>, TParseErr<'src>> {
+        let mut inner = vec![];
+        loop {
+            self.skip_blanks();
+            match self.peek() {
+                None => return Err(TParseErr::with_span(self.span_from(start), TParseErrKind::UnclosedDelimiter)),
+                Some(c) if c == closer => {
+                    self.bump();
+                    return Ok(inner);
+                }
+                Some('\n') => {
+                    self.bump();
+                }
+                Some(_) => inner.push(self.token()?),
+            }
+        }
+    }
+
+    /// `□ Name ← ⟨ ... ⟩`, which must be a statement of its own
+    fn data(&mut self) -> Result<ExprToken<'src>, TParseErr<'src>> {
+        let start = self.pos;
+        let bad = |l: &Self| TParseErr::with_span(l.span_from(start), TParseErrKind::BadData);
+        self.bump();
+        self.skip_blanks();
+        let name = self.name().ok_or_else(|| bad(self))?;
+        if !self.eat('←') || !self.eat('⟨') {
+            return Err(bad(self));
+        }
+        let entries = self.entries(start)?;
+        let fields: Option<Vec<_>> = entries.iter().map(|e| e.field.clone()).collect();
+        let variants = match fields {
+            // A product type
+            Some(fields) => vec![VariantSyntax { name: None, fields }],
+            None => entries
+                .into_iter()
+                .map(|e| e.variant.ok_or_else(|| bad(self)))
+                .collect::<Result<_, _>>()?,
+        };
+        self.skip_blanks();
+        if !matches!(self.peek(), None | Some('\n' | '⋄')) {
+            return Err(bad(self));
+        }
+        Ok(ExprToken {
+            kind: ExprTokenKind::Data(Box::new(DataSyntax { name, variants })),
+            span: self.span_from(start),
+        })
+    }
+
+    /// Up to and including the closing `⟩`, separated by diamonds or newlines
+    fn entries(&mut self, start: usize) -> Result<Vec<Entry>, TParseErr<'src>> {
+        let bad = |l: &Self| TParseErr::with_span(l.span_from(start), TParseErrKind::BadData);
+        let mut entries = vec![];
+        loop {
+            self.skip_blanks();
+            match self.peek() {
+                Some('\n' | '⋄') => {
+                    self.bump();
+                    continue;
+                }
+                Some('⟩') => {
+                    self.bump();
+                    return Ok(entries);
+                }
+                _ => {}
+            }
+            let name = match self.eat('_') {
+                true => None,
+                false => Some(self.name().ok_or_else(|| bad(self))?),
+            };
+            let entry = if self.eat(':') {
+                let ty = self.field_type().ok_or_else(|| bad(self))?;
+                Entry {
+                    field: name.map(|name| (name, ty)),
+                    variant: None,
+                }
+            } else if self.eat('←') {
+                if !self.eat('⟨') {
+                    return Err(bad(self));
+                }
+                let fields = self.entries(start)?.into_iter().map(|e| e.field).collect::<Option<_>>();
+                Entry {
+                    field: None,
+                    variant: Some(VariantSyntax {
+                        name,
+                        fields: fields.ok_or_else(|| bad(self))?,
+                    }),
+                }
+            } else {
+                Entry {
+                    field: None,
+                    variant: Some(VariantSyntax { name, fields: vec![] }),
+                }
+            };
+            entries.push(entry);
+        }
+    }
+
+    /// `'Name` or `'(rank, Name)`
+    fn field_type(&mut self) -> Option<FieldType> {
+        if !self.eat('\'') {
+            return None;
+        }
+        if !self.eat('(') {
+            return self.name().map(FieldType::Named);
+        }
+        self.skip_blanks();
+        let rank = self.nat()?;
+        if !self.eat(',') {
+            return None;
+        }
+        self.skip_blanks();
+        let scalar = self.name()?;
+        self.eat(')').then_some(FieldType::Array { rank, scalar })
+    }
+
+    /// `: ∀T (Num T) ⋯ 'α-type » Name « 'ω-type ↳ 'output-type`, which must be a statement of its
+    /// own. Either argument may be left out, and `↳` may start the next line
+    fn signature(&mut self) -> Result<ExprToken<'src>, TParseErr<'src>> {
+        let start = self.pos;
+        let bad = |l: &Self| TParseErr::with_span(l.span_from(start), TParseErrKind::BadSignature);
+        self.bump();
+        let mut vars = vec![];
+        let mut constraints = vec![];
+        if self.eat('∀') {
+            self.skip_blanks();
+            loop {
+                if let Some(var) = self.name() {
+                    vars.push(var);
+                } else if self.eat('(') {
+                    self.skip_blanks();
+                    let class = self.name().ok_or_else(|| bad(self))?;
+                    self.skip_blanks();
+                    let var = self.name().ok_or_else(|| bad(self))?;
+                    self.skip_blanks();
+                    if !self.eat(')') {
+                        return Err(bad(self));
+                    }
+                    constraints.push((class, var));
+                } else {
+                    break;
+                }
+                self.skip_blanks();
+            }
+        }
+        // Without a type for ⍺, the name being given a type comes right after the variables
+        let (alpha, name) = match self.peek() {
+            Some('\'') => {
+                let alpha = self.type_syntax().ok_or_else(|| bad(self))?;
+                if !self.eat('»') {
+                    return Err(bad(self));
+                }
+                self.skip_blanks();
+                (Some(alpha), self.name().ok_or_else(|| bad(self))?)
+            }
+            _ => match self.name() {
+                Some(name) => (None, name),
+                None => (None, vars.pop().ok_or_else(|| bad(self))?),
+            },
+        };
+        let omega = match self.eat('«') {
+            true => {
+                self.skip_blanks();
+                Some(self.type_syntax().ok_or_else(|| bad(self))?)
+            }
+            false => None,
+        };
+        loop {
+            self.skip_blanks();
+            if self.peek() != Some('\n') {
+                break;
+            }
+            self.bump();
+        }
+        if !self.eat('↳') {
+            return Err(bad(self));
+        }
+        self.skip_blanks();
+        let output = self.type_syntax().ok_or_else(|| bad(self))?;
+        self.skip_blanks();
+        if !matches!(self.peek(), None | Some('\n' | '⋄')) {
+            return Err(bad(self));
+        }
+        let syntax = SignatureSyntax {
+            name,
+            vars,
+            constraints,
+            alpha,
+            omega,
+            output,
+        };
+        Ok(ExprToken {
+            kind: ExprTokenKind::Signature(Box::new(syntax)),
+            span: self.span_from(start),
+        })
+    }
+
+    /// `'Scalar` or `'(⍤R, ≢=5, ⍴S, Scalar)`, see [`TypeSyntax`]
+    fn type_syntax(&mut self) -> Option<TypeSyntax> {
+        if !self.eat('\'') {
+            return None;
+        }
+        if !self.eat('(') {
+            return Some(TypeSyntax {
+                scalar: self.name()?,
+                rank: Some(Bound::Nat(0)),
+                ..Default::default()
+            });
+        }
+        let mut ty = TypeSyntax::default();
+        loop {
+            self.skip_blanks();
+            let axes = match self.peek()? {
+                '0'..='9' => {
+                    ty.rank = Some(Bound::Nat(self.nat()?));
+                    None
+                }
+                '⍤' => Some(&mut ty.rank),
+                '≢' => Some(&mut ty.length),
+                '⍴' => Some(&mut ty.shape),
+                _ => break,
+            };
+            if let Some(slot) = axes {
+                let shape = self.bump() == Some('⍴');
+                *slot = match self.peek() {
+                    Some('[') if shape => {
+                        self.bump();
+                        Some(Bound::Axes(self.axes()?))
+                    }
+                    Some('=') if shape => {
+                        self.bump();
+                        let mut sh = vec![self.nat()?];
+                        while self.peek() == Some('_') {
+                            self.bump();
+                            sh.push(self.nat()?);
+                        }
+                        Some(Bound::Shape(sh))
+                    }
+                    Some('=') => {
+                        self.bump();
+                        Some(Bound::Nat(self.nat()?))
+                    }
+                    _ => self.relative_bound(),
+                };
+            }
+            if !self.eat(',') {
+                return None;
+            }
+        }
+        self.eat('\'');
+        self.skip_blanks();
+        ty.scalar = self.name()?;
+        self.eat(')').then_some(ty)
+    }
+
+    /// `R`, `₁` or nothing, possibly followed by `-1`, `+1` or `⇌`. Nothing at all only says the axes
+    /// are there
+    fn relative_bound(&mut self) -> Option<Bound> {
+        let base = match self.peek() {
+            Some(c) if is_name_start(c) => Some(Bound::Var(self.name()?)),
+            Some('₁') => {
+                self.bump();
+                Some(Bound::Input(Some(Side::Alpha)))
+            }
+            Some('₂') => {
+                self.bump();
+                Some(Bound::Input(Some(Side::Omega)))
+            }
+            _ => None,
+        };
+        let relative = || Box::new(base.clone().unwrap_or(Bound::Input(None)));
+        match self.peek() {
+            Some(sign @ ('+' | '-')) => {
+                self.bump();
+                let n = self.nat()? as i32;
+                Some(Bound::Offset(relative(), if sign == '-' { -n } else { n }))
+            }
+            Some('⇌') => {
+                self.bump();
+                Some(Bound::Reversed(relative()))
+            }
+            _ => base,
+        }
+    }
+
+    /// `rows=3, cols=?, 2]`, each being a name, a size or both
+    fn axes(&mut self) -> Option<Vec<(Option<String>, Option<u32>)>> {
+        let mut axes = vec![];
+        loop {
+            self.skip_blanks();
+            if self.peek() == Some(']') && axes.is_empty() {
+                self.bump();
+                return Some(axes);
+            }
+            let name = self.name();
+            if name.is_some() && !self.eat('=') {
+                axes.push((name, None));
+            } else {
+                self.skip_blanks();
+                match self.peek()? {
+                    '?' => {
+                        self.bump();
+                        axes.push((name, None));
+                    }
+                    _ => axes.push((name, Some(self.nat()?))),
+                }
+            }
+            if self.eat(']') {
+                return Some(axes);
+            }
+            if !self.eat(',') {
+                return None;
+            }
+        }
+    }
+
+    fn nat(&mut self) -> Option<u32> {
+        let start = self.pos;
+        while matches!(self.peek(), Some('0'..='9')) {
+            self.bump();
+        }
+        self.src[start..self.pos].parse().ok()
+    }
+
+    fn name(&mut self) -> Option<String> {
+        let start = self.pos;
+        if !self.peek().is_some_and(is_name_start) {
+            return None;
+        }
+        while self.peek().is_some_and(is_name_continue) {
+            self.bump();
+        }
+        Some(self.src[start..self.pos].to_string())
+    }
+
+    /// Consumes `c` if it's next after some blanks
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_blanks();
+        let found = self.peek() == Some(c);
+        if found {
+            self.bump();
+        }
+        found
+    }
+
+    /// A single item, or several of them joined by underscores into a strand
+    fn token(&mut self) -> Result<ExprToken<'src>, TParseErr<'src>> {
+        let start = self.pos;
+        let first = self.item()?;
+        if self.peek() != Some('_') {
+            return Ok(first);
+        }
+        let mut items = vec![first];
+        while self.peek() == Some('_') {
+            self.bump();
+            match self.peek() {
+                Some(c) if !c.is_whitespace() && c != '⋄' && c != '⍝' => items.push(self.item()?),
+                _ => return Err(TParseErr::with_span(self.span_from(start), TParseErrKind::BadStrand)),
+            }
+        }
+        Ok(ExprToken {
+            kind: ExprTokenKind::Strand(items),
+            span: self.span_from(start),
+        })
+    }
+
+    fn item(&mut self) -> Result<ExprToken<'src>, TParseErr<'src>> {
+        use TParseErrKind as EK;
+        let start = self.pos;
+        let c = self.bump().expect("caller checked for eof");
+        let kind = match c {
+            '0'..='9' => {
+                while matches!(self.peek(), Some('0'..='9')) {
+                    self.bump();
+                }
+                ExprTokenKind::Number
+            }
+            '"' | '\'' => {
+                loop {
+                    match self.bump() {
+                        None => return Err(TParseErr::with_span(self.span_from(start), EK::UnterminatedLiteral)),
+                        Some('\\') => {
+                            self.bump();
+                        }
+                        Some(q) if q == c => break,
+                        Some(_) => {}
+                    }
+                }
+                if c == '"' {
+                    ExprTokenKind::String
+                } else if unescape(&self.src[start + 1..self.pos - 1]).chars().count() == 1 {
+                    ExprTokenKind::Char
+                } else {
+                    return Err(TParseErr::with_span(self.span_from(start), EK::BadCharLiteral));
+                }
+            }
+            '(' => ExprTokenKind::Parenthesized(self.bracketed(start, ')')?),
+            '⦅' => ExprTokenKind::Train(self.bracketed(start, '⦆')?),
+            '[' => ExprTokenKind::Bracket(self.statements(Some(']'))?),
+            '{' => {
+                self.depth += 1;
+                if self.depth > ARGUMENTS.len() {
+                    return Err(TParseErr::with_span(self.span_from(start), EK::DfnTooDeep));
+                }
+                let body = self.statements(Some('}'))?;
+                let depth = self.depth;
+                self.depth -= 1;
+                let (a, w) = ARGUMENTS[depth - 1];
+                let used = |name| body.iter().any(|st| uses(self.src, st, name));
+                let cat = match (used(a), used(w)) {
+                    (true, true) => Category::Dv,
+                    (true, false) => Category::Av,
+                    (false, true) => Category::Ov,
+                    (false, false) => return Err(TParseErr::with_span(self.span_from(start), EK::DfnNoArguments)),
+                };
+                ExprTokenKind::Dfn { body, cat, depth }
+            }
+            '□' => return Err(TParseErr::with_span(self.span_from(start), EK::BadData)),
+            '⊔' => match self.eat('⟨') {
+                true => ExprTokenKind::Match(self.statements(Some('⟩'))?),
+                false => return Err(TParseErr::with_span(self.span_from(start), EK::BadMatch)),
+            },
+            '→' => ExprTokenKind::Then,
+            '_' => ExprTokenKind::Wildcard,
+            '.' if self.peek().is_some_and(is_name_start) => {
+                while self.peek().is_some_and(is_name_continue) {
+                    self.bump();
+                }
+                ExprTokenKind::Field
+            }
+            ')' | '}' | '⦆' | ']' => return Err(TParseErr::with_span(self.span_from(start), EK::UnexpectedCloser)),
+            '←' => ExprTokenKind::Arrow,
+            // System names, e.g. `⎕args`
+            '⎕' if self.peek().is_some_and(is_name_start) => {
+                while self.peek().is_some_and(is_name_continue) {
+                    self.bump();
+                }
+                ExprTokenKind::ArrayName
+            }
+            c if is_name_start(c) => {
+                while self.peek().is_some_and(is_name_continue) {
+                    self.bump();
+                }
+                // Each dfn can only see its own arguments and those of the ones it's in
+                if argument_depth(&self.src[start..self.pos]).is_some_and(|d| d > self.depth) {
+                    return Err(TParseErr::with_span(self.span_from(start), EK::ArgumentOutOfScope));
+                }
+                ExprTokenKind::ArrayName
+            }
+            c => match Primitive::from_glyph(c) {
+                Some(p) => match p.category() {
+                    Category::Av => ExprTokenKind::PrimAVerb,
+                    Category::Ov => ExprTokenKind::PrimOVerb,
+                    Category::Dv => ExprTokenKind::PrimDVerb,
+                    Category::Aa => ExprTokenKind::PrimAAdverb,
+                    Category::Oa => ExprTokenKind::PrimOAdverb,
+                    Category::Da => ExprTokenKind::PrimDAdverb,
+                    Category::Jot => ExprTokenKind::Jot,
+                    c => unreachable!("primitives can't be of category {c:?}"),
+                },
+                None => return Err(TParseErr::with_span(self.span_from(start), EK::UnknownGlyph)),
+            },
+        };
+        Ok(ExprToken {
+            kind,
+            span: self.span_from(start),
+        })
+    }
+}
+
+/// Something between the angle brackets of a declaration, either a field or a variant
+struct Entry {
+    field: Option<(String, FieldType)>,
+    variant: Option<VariantSyntax>,
+}
+
+fn is_name_start(c: char) -> bool {
+    c.is_alphabetic()
+}
+fn is_name_continue(c: char) -> bool {
+    c.is_alphanumeric() || ('₀'..='₉').contains(&c) || c == '′'
+}
+
+/// The contents of a string or char literal, without the quotes
+pub(crate) fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        out.push(match c {
+            '\\' => match chars.next() {
+                Some('n') => '\n',
+                Some('t') => '\t',
+                Some(c) => c,
+                None => break,
+            },
+            c => c,
+        });
+    }
+    out
+}
+
+/// The names a match pattern binds. In `Variant a b` the first name is that of the variant
+fn pattern_names<'a>(src: &'a str, pattern: &[ExprToken<'a>]) -> Vec<&'a str> {
+    let fields = match pattern {
+        [_, fields @ ..] if !fields.is_empty() => fields,
+        _ => pattern,
+    };
+    fields
+        .iter()
+        .flat_map(|t| match &t.kind {
+            ExprTokenKind::Parenthesized(inner) => pattern_names(src, inner),
+            ExprTokenKind::Bracket(items) => items.iter().flat_map(|item| pattern_names(src, item)).collect(),
+            ExprTokenKind::ArrayName
+            | ExprTokenKind::AVerbName
+            | ExprTokenKind::OVerbName
+            | ExprTokenKind::DVerbName => vec![t.span.slice(src)],
+            _ => vec![],
+        })
+        .collect()
+}
+
+/// Names are lexed as [`ExprTokenKind::ArrayName`], this gives them the kind of whatever
+/// they're bound to. A name right before a `←` is left alone, as it's being (re)assigned
+pub(crate) fn resolve_names<'src>(
+    src: &str,
+    ts: &mut [ExprToken<'src>],
+    lookup: &dyn Fn(&str) -> Option<Category>,
+) {
+    // Dfns may refer to themselves
+    if let [name, ExprToken { kind: ExprTokenKind::Arrow, .. }, ExprToken { kind: ExprTokenKind::Dfn { cat, .. }, .. }] = ts {
+        let (name, cat) = (name.span.slice(src), *cat);
+        return resolve_names(src, &mut ts[2..], &|n| if n == name { Some(cat) } else { lookup(n) });
+    }
+    for i in 0..ts.len() {
+        let assigned = matches!(ts.get(i + 1), Some(ExprToken { kind: ExprTokenKind::Arrow, .. }));
+        match &mut ts[i].kind {
+            ExprTokenKind::Parenthesized(inner) | ExprTokenKind::Train(inner) | ExprTokenKind::Strand(inner) => {
+                resolve_names(src, inner, lookup)
+            }
+            // Names bound by a pattern are arrays in the body of its arm
+            ExprTokenKind::Match(arms) => {
+                for arm in arms {
+                    let then = arm.iter().position(|t| t.kind == ExprTokenKind::Then).unwrap_or(arm.len());
+                    let (pattern, body) = arm.split_at_mut(then);
+                    let bound = pattern_names(src, pattern);
+                    resolve_names(src, body, &|name| match bound.contains(&name) {
+                        true => Some(Category::A),
+                        false => lookup(name),
+                    });
+                }
+            }
+            // A pattern being assigned to is made of names, which are left alone
+            ExprTokenKind::Bracket(items) if !assigned => {
+                for item in items {
+                    resolve_names(src, item, lookup);
+                }
+            }
+            // Statements may refer to what the previous ones assigned
+            ExprTokenKind::Dfn { body, .. } => {
+                let mut locals = HashMap::new();
+                for statement in body {
+                    resolve_names(src, statement, &|name| locals.get(name).copied().or_else(|| lookup(name)));
+                    match &statement[..] {
+                        [ExprToken { kind: ExprTokenKind::Bracket(names), .. }, ExprToken { kind: ExprTokenKind::Arrow, .. }, ..] => {
+                            for name in names.iter().filter_map(|item| item.first()) {
+                                locals.insert(name.span.slice(src), Category::A);
+                            }
+                        }
+                        [name, ExprToken { kind: ExprTokenKind::Arrow, .. }, ..] => {
+                            if let Ok(tree) = parse_expr(src, statement, name.span) {
+                                locals.insert(name.span.slice(src), tree.category());
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            ExprTokenKind::ArrayName if !assigned => {
+                ts[i].kind = match lookup(ts[i].span.slice(src)) {
+                    Some(Category::Av) => ExprTokenKind::AVerbName,
+                    Some(Category::Ov) => ExprTokenKind::OVerbName,
+                    Some(Category::Dv) => ExprTokenKind::DVerbName,
+                    Some(Category::Oa) => ExprTokenKind::OAdverbName,
+                    Some(Category::Da) => ExprTokenKind::DAdverbName,
+                    _ => ExprTokenKind::ArrayName,
+                }
+            }
+            _ => {}
+        }
+    }
+}
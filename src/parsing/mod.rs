@@ -3,28 +3,103 @@
 //! Structured as a tree, where each kind of branch is assigned a Category
 
 use crate::{ast::Sp, *};
-use std::mem;
 mod expr;
+mod lexing;
 pub(crate) use expr::*;
+pub(crate) use lexing::*;
 
 #[derive(Debug, Clone)]
 pub struct TParseErr<'src> {
-    span: Sp<'src>,
-    kind: TParseErrKind,
+    pub(crate) span: Sp<'src>,
+    pub(crate) kind: TParseErrKind,
 }
 
 impl<'src> TParseErr<'src> {
-    fn at(t: ExprToken<'src>, kind: TParseErrKind) -> Self {
-        Self { kind, span: t.span }
-    }
     fn with_span(span: Sp<'src>, kind: TParseErrKind) -> Self {
         Self { kind, span }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TParseErrKind {
-    UnexpectedEndOfExpression,
+    /// Two adjacent pieces of an expression that can't go together, e.g. `1 2` (stranding is explicit)
+    CannotBind,
+    /// Expression that isn't a value, e.g. a lone modifier
+    Incomplete,
+    UnknownGlyph,
+    UnterminatedLiteral,
+    /// Char literals hold exactly one char
+    BadCharLiteral,
+    UnclosedDelimiter,
+    UnexpectedCloser,
+    /// Dfns can only be nested so many times, as each level needs its own argument names
+    DfnTooDeep,
+    /// A dfn argument used outside of the dfn it belongs to
+    ArgumentOutOfScope,
+    /// A dfn that uses neither of its arguments
+    DfnNoArguments,
+    /// Trains are either two verbs, the second monadic, or three with a dyadic one in the middle
+    /// and two of the same kind around it
+    BadTrain,
+    /// Strands join arrays, and an underscore must have one on each side, e.g. `1_2_3`
+    BadStrand,
+    /// Only names can be destructured into, e.g. `[a⋄b] ← 1_2`
+    BadPattern,
+    /// A malformed `□` declaration, or one that isn't a statement of its own at the top level
+    BadData,
+    /// A malformed `:` signature, or one that isn't a statement of its own at the top level
+    BadSignature,
+    /// `⊔` takes arms, `⟨ pattern → result ⋄ ... ⟩`
+    BadMatch,
+}
+
+impl std::fmt::Display for TParseErrKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use TParseErrKind as K;
+        match self {
+            K::CannotBind => write!(f, "these can't go together"),
+            K::Incomplete => write!(f, "incomplete expression"),
+            K::UnknownGlyph => write!(f, "unknown glyph"),
+            K::UnterminatedLiteral => write!(f, "unterminated literal"),
+            K::BadCharLiteral => write!(f, "char literals hold exactly one char"),
+            K::UnclosedDelimiter => write!(f, "unclosed delimiter"),
+            K::UnexpectedCloser => write!(f, "closing delimiter without an opening one"),
+            K::DfnTooDeep => write!(f, "dfns nested too deeply"),
+            K::ArgumentOutOfScope => write!(f, "argument used outside of its dfn"),
+            K::DfnNoArguments => write!(f, "dfn uses neither of its arguments"),
+            K::BadTrain => write!(f, "malformed train"),
+            K::BadStrand => write!(f, "malformed strand"),
+            K::BadPattern => write!(f, "only names can be destructured into"),
+            K::BadData => write!(f, "malformed □ declaration"),
+            K::BadSignature => write!(f, "malformed signature"),
+            K::BadMatch => write!(f, "malformed ⊔"),
+        }
+    }
+}
+
+impl TParseErrKind {
+    /// Identifies the kind of error for tooling. Never reused or renumbered
+    pub fn code(self) -> &'static str {
+        use TParseErrKind as K;
+        match self {
+            K::CannotBind => "P001",
+            K::Incomplete => "P002",
+            K::UnknownGlyph => "P003",
+            K::UnterminatedLiteral => "P004",
+            K::BadCharLiteral => "P005",
+            K::UnclosedDelimiter => "P006",
+            K::UnexpectedCloser => "P007",
+            K::DfnTooDeep => "P008",
+            K::ArgumentOutOfScope => "P009",
+            K::DfnNoArguments => "P010",
+            K::BadTrain => "P011",
+            K::BadStrand => "P012",
+            K::BadPattern => "P013",
+            K::BadData => "P014",
+            K::BadSignature => "P015",
+            K::BadMatch => "P016",
+        }
+    }
 }
 
 #[test]
@@ -46,13 +121,13 @@ fn babys_first_parsing() {
         },
     ];
 
-    let expr = parse_expr(&ts, Sp::ZERO ).unwrap();
+    let expr = parse_expr(_source, &ts, Sp::ZERO).unwrap();
     let expected = ExprTree::DyadicVerbCall {
         verb: Box::new(ExprTree::Leaf {
             cat: Category::Dv,
             t: ExprToken {
                 kind: ExprTokenKind::PrimDVerb,
-                span: Sp::new(0, 1),
+                span: Sp::new(1, 2),
             },
         }),
         alpha: Box::new(ExprTree::Leaf {
@@ -66,13 +141,20 @@ fn babys_first_parsing() {
             cat: Category::A,
             t: ExprToken {
                 kind: ExprTokenKind::Number,
-                span: Sp::new(0, 1),
+                span: Sp::new(2, 3),
             },
         }),
     };
     assert_eq!(expr, expected);
 }
 
+#[test]
+fn product_is_unnamed_variant() {
+    let product = lex("□ Card ← ⟨ value: 'Value ⋄ suit: 'Suit ⟩").unwrap();
+    let sum = lex("□ Card ← ⟨ _ ← ⟨ value: 'Value ⋄ suit: 'Suit ⟩ ⟩").unwrap();
+    assert_eq!(product[0][0].kind, sum[0][0].kind);
+}
+
 //#[test]
 //fn bunda_gerth_binding_powers() {
 //    // See README.md/Bunda-Gerth for the actual table
@@ -1,19 +1,902 @@
-mod arithmetic {
+use crate::{Primitive as P, *};
+
+/// Everything about each primitive, in the order the README lists them. The lexer gets the token
+/// kind from the category, the type checker the type from the rule, `⍜` the inverse from `under`,
+/// and the repl, input methods and `tqo docs` everything else
+#[rustfmt::skip]
+pub(crate) const PRIMITIVES: &[PrimitiveInfo] = &[
+    PrimitiveInfo {
+        primitive: P::Negate, glyph: '¯', name: "Negate", alias: "negate", key: Some('2'),
+        category: Category::Ov, rule: Rule::Signed(Side::Omega), under: &[(Side::Omega, Under::Inverse(P::Negate, Side::Omega))],
+        doc: "ω with its sign flipped",
+    },
+    PrimitiveInfo {
+        primitive: P::Not, glyph: '¬', name: "Not", alias: "not", key: Some('~'),
+        category: Category::Av, rule: Rule::Numeric(Side::Alpha), under: &[(Side::Alpha, Under::Inverse(P::Not, Side::Alpha))],
+        doc: "1 for 0 and 0 for 1",
+    },
+    PrimitiveInfo {
+        primitive: P::Sign, glyph: '±', name: "Sign", alias: "sign", key: Some('p'),
+        category: Category::Av, rule: Rule::Signed(Side::Alpha), under: &[],
+        doc: "¯1, 0 or 1 as α is negative, zero or positive",
+    },
+    PrimitiveInfo {
+        primitive: P::Add, glyph: '+', name: "Add", alias: "add", key: None,
+        category: Category::Dv, rule: Rule::Promoted, under: &[(Side::Alpha, Under::Inverse(P::Subtract, Side::Alpha)), (Side::Omega, Under::Inverse(P::Subtract, Side::Alpha))],
+        doc: "α plus ω",
+    },
+    PrimitiveInfo {
+        primitive: P::Subtract, glyph: '-', name: "Subtract", alias: "subtract", key: None,
+        category: Category::Dv, rule: Rule::SignedPromoted, under: &[(Side::Alpha, Under::Inverse(P::Add, Side::Alpha)), (Side::Omega, Under::Inverse(P::Subtract, Side::Omega))],
+        doc: "α minus ω",
+    },
+    PrimitiveInfo {
+        primitive: P::Multiply, glyph: '×', name: "Multiply", alias: "multiply", key: Some('-'),
+        category: Category::Dv, rule: Rule::Promoted, under: &[],
+        doc: "α times ω",
+    },
+    PrimitiveInfo {
+        primitive: P::Divide, glyph: '÷', name: "Divide", alias: "divide", key: Some('='),
+        category: Category::Dv, rule: Rule::Promoted, under: &[],
+        doc: "α divided by ω, rounded down",
+    },
+    PrimitiveInfo {
+        primitive: P::Modulo, glyph: '◿', name: "Modulo", alias: "modulo", key: Some('m'),
+        category: Category::Dv, rule: Rule::Promoted, under: &[],
+        doc: "What's left of α after dividing it by ω, never negative",
+    },
+    PrimitiveInfo {
+        primitive: P::AbsoluteValue, glyph: '⌵', name: "Absolute Value", alias: "abs", key: Some('V'),
+        category: Category::Av, rule: Rule::Numeric(Side::Alpha), under: &[],
+        doc: "α without its sign",
+    },
+    PrimitiveInfo {
+        primitive: P::Reciprocal, glyph: '⨪', name: "Reciprocal", alias: "reciprocal", key: Some('1'),
+        category: Category::Av, rule: Rule::Unknown, under: &[(Side::Alpha, Under::Inverse(P::Reciprocal, Side::Alpha))],
+        doc: "1 divided by α. Not there yet, as it needs floats",
+    },
+    PrimitiveInfo {
+        primitive: P::Sqrt, glyph: '√', name: "Sqrt", alias: "sqrt", key: Some('v'),
+        category: Category::Av, rule: Rule::Unknown, under: &[],
+        doc: "The square root of α. Not there yet, as it needs floats",
+    },
+    PrimitiveInfo {
+        primitive: P::Maximum, glyph: '↥', name: "Maximum", alias: "max", key: Some('s'),
+        category: Category::Dv, rule: Rule::Promoted, under: &[],
+        doc: "The greater of α and ω",
+    },
+    PrimitiveInfo {
+        primitive: P::Minimum, glyph: '↧', name: "Minimum", alias: "min", key: Some('d'),
+        category: Category::Dv, rule: Rule::Promoted, under: &[],
+        doc: "The lesser of α and ω",
+    },
+    PrimitiveInfo {
+        primitive: P::Equals, glyph: '=', name: "Equals", alias: "equals", key: None,
+        category: Category::Dv, rule: Rule::Comparison, under: &[],
+        doc: "1 where α is ω, else 0",
+    },
+    PrimitiveInfo {
+        primitive: P::NotEquals, glyph: '≠', name: "Not equals", alias: "notequals", key: Some('8'),
+        category: Category::Dv, rule: Rule::Comparison, under: &[],
+        doc: "1 where α isn't ω, else 0",
+    },
+    PrimitiveInfo {
+        primitive: P::LessThan, glyph: '<', name: "Less than", alias: "less", key: None,
+        category: Category::Dv, rule: Rule::Comparison, under: &[],
+        doc: "1 where α is less than ω, else 0",
+    },
+    PrimitiveInfo {
+        primitive: P::LessOrEqual, glyph: '≤', name: "Less or equal", alias: "lessorequal", key: Some('4'),
+        category: Category::Dv, rule: Rule::Comparison, under: &[],
+        doc: "1 where α is at most ω, else 0",
+    },
+    PrimitiveInfo {
+        primitive: P::GreaterThan, glyph: '>', name: "Greater than", alias: "greater", key: None,
+        category: Category::Dv, rule: Rule::Comparison, under: &[],
+        doc: "1 where α is greater than ω, else 0",
+    },
+    PrimitiveInfo {
+        primitive: P::GreaterOrEqual, glyph: '≥', name: "Greater or equal", alias: "greaterorequal", key: Some('6'),
+        category: Category::Dv, rule: Rule::Comparison, under: &[],
+        doc: "1 where α is at least ω, else 0",
+    },
+    PrimitiveInfo {
+        primitive: P::And, glyph: '∧', name: "And", alias: "and", key: Some('0'),
+        category: Category::Dv, rule: Rule::Logic, under: &[],
+        doc: "1 where both α and ω are 1, else 0",
+    },
+    PrimitiveInfo {
+        primitive: P::Left, glyph: '⊣', name: "Left", alias: "left", key: Some('|'),
+        category: Category::Dv, rule: Rule::Same(Side::Alpha), under: &[],
+        doc: "α",
+    },
+    PrimitiveInfo {
+        primitive: P::Right, glyph: '⊢', name: "Right", alias: "right", key: Some('\\'),
+        category: Category::Dv, rule: Rule::Same(Side::Omega), under: &[],
+        doc: "ω",
+    },
+    PrimitiveInfo {
+        primitive: P::Transpose, glyph: '⍉', name: "Transpose", alias: "transpose", key: Some('^'),
+        category: Category::Av, rule: Rule::Transpose, under: &[(Side::Alpha, Under::Structural), (Side::Omega, Under::Structural)],
+        doc: "α with its axes in reverse order",
+    },
+    PrimitiveInfo {
+        primitive: P::Range, glyph: '⇡', name: "Range", alias: "range", key: Some('i'),
+        category: Category::Av, rule: Rule::Range, under: &[],
+        doc: "The naturals up to α, from 0",
+    },
+    PrimitiveInfo {
+        primitive: P::Reverse, glyph: '⇌', name: "Reverse", alias: "reverse", key: Some('R'),
+        category: Category::Av, rule: Rule::Same(Side::Alpha), under: &[(Side::Alpha, Under::Structural), (Side::Omega, Under::Structural)],
+        doc: "α's major cells in reverse order",
+    },
+    PrimitiveInfo {
+        primitive: P::Tally, glyph: '≢', name: "Tally", alias: "tally", key: Some('t'),
+        category: Category::Av, rule: Rule::Count, under: &[],
+        doc: "How many major cells α has, 1 for scalars",
+    },
+    PrimitiveInfo {
+        primitive: P::Match, glyph: '≍', name: "Match", alias: "match", key: Some(':'),
+        category: Category::Dv, rule: Rule::Count, under: &[],
+        doc: "1 if α and ω are the same array, else 0",
+    },
+    PrimitiveInfo {
+        primitive: P::Notmatch, glyph: '≭', name: "Notmatch", alias: "notmatch", key: Some(';'),
+        category: Category::Dv, rule: Rule::Count, under: &[],
+        doc: "1 if α and ω are different arrays, else 0",
+    },
+    PrimitiveInfo {
+        primitive: P::Shape, glyph: '⍴', name: "Shape", alias: "shape", key: Some('r'),
+        category: Category::Av, rule: Rule::Shape, under: &[],
+        doc: "The length of each of α's axes",
+    },
+    PrimitiveInfo {
+        primitive: P::Select, glyph: '⊏', name: "Select", alias: "select", key: Some('x'),
+        category: Category::Dv, rule: Rule::Select, under: &[(Side::Alpha, Under::Structural)],
+        doc: "α's major cells at each of the indices in ω",
+    },
+    PrimitiveInfo {
+        primitive: P::Pick, glyph: '⊡', name: "Pick", alias: "pick", key: Some('X'),
+        category: Category::Dv, rule: Rule::Pick, under: &[(Side::Alpha, Under::Structural)],
+        doc: "α's element at the index vector ω",
+    },
+    PrimitiveInfo {
+        primitive: P::Take, glyph: '↑', name: "Take", alias: "take", key: Some('y'),
+        category: Category::Dv, rule: Rule::Cells, under: &[(Side::Alpha, Under::Structural)],
+        doc: "α's first ω major cells",
+    },
+    PrimitiveInfo {
+        primitive: P::Drop, glyph: '↓', name: "Drop", alias: "drop", key: Some('u'),
+        category: Category::Dv, rule: Rule::Cells, under: &[(Side::Alpha, Under::Structural)],
+        doc: "α without its first ω major cells",
+    },
+    PrimitiveInfo {
+        primitive: P::Table, glyph: '⊞', name: "Table", alias: "table", key: Some('o'),
+        category: Category::Oa, rule: Rule::Derived, under: &[],
+        doc: "ω between every major cell of α and every one of ω",
+    },
+    PrimitiveInfo {
+        primitive: P::Evert, glyph: '⧋', name: "Evert", alias: "evert", key: Some('E'),
+        category: Category::Oa, rule: Rule::Derived, under: &[],
+        doc: "ω acting on the trailing axis rather than the leading one",
+    },
+    PrimitiveInfo {
+        primitive: P::Under, glyph: '⍜', name: "Under", alias: "under", key: Some('U'),
+        category: Category::Da, rule: Rule::Derived, under: &[],
+        doc: "ω applied to what α gives, which is then put back",
+    },
+    PrimitiveInfo {
+        primitive: P::Repeat, glyph: '⍣', name: "Repeat", alias: "repeat", key: Some('*'),
+        category: Category::Da, rule: Rule::Derived, under: &[],
+        doc: "ω applied α times, or until the function α holds of the last two results",
+    },
+    PrimitiveInfo {
+        primitive: P::Jot, glyph: '∘', name: "Jot", alias: "jot", key: Some('j'),
+        category: Category::Jot, rule: Rule::Derived, under: &[],
+        doc: "The verb with the array on one side bound as its argument, or both verbs one after the other",
+    },
+];
+
+pub(crate) struct PrimitiveInfo {
+    pub(crate) primitive: Primitive,
+    pub(crate) glyph: char,
+    /// As the README has it
+    pub(crate) name: &'static str,
+    /// How it's spelled without its glyph
+    pub(crate) alias: &'static str,
+    /// Typed as a backtick then this key. Those on keyboards already don't have one
+    pub(crate) key: Option<char>,
+    /// Which kind of token it lexes as
+    pub(crate) category: Category,
+    pub(crate) rule: Rule,
+    /// How `⍜` puts back what it did to an argument on each side, see [`Primitive::under`]
+    pub(crate) under: &'static [(Side, Under)],
+    pub(crate) doc: &'static str,
+}
+
+/// What the type of a primitive's output is made of, given its arguments', see `primitive_type`.
+/// Which arguments it takes comes from its category
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Rule {
+    /// That of the argument on this side
+    Same(Side),
+    /// That of the argument on this side, which must be numeric
+    Numeric(Side),
+    /// That of the argument on this side, with a scalar type that can hold its negation
+    Signed(Side),
+    /// Nothing is known about it yet
+    Unknown,
+    /// Pervasive, of the scalar type both arguments' are promoted to
+    Promoted,
+    /// Pervasive, of the signed type both arguments' are promoted to
+    SignedPromoted,
+    /// Pervasive booleans
+    Comparison,
+    /// Pervasive booleans, of booleans
+    Logic,
+    /// A scalar natural
+    Count,
+    /// A vector with a natural for each axis
+    Shape,
+    /// Major cells of α, arranged like the indices in ω
+    Select,
+    /// A scalar of α's type, at an index vector ω
+    Pick,
+    /// Major cells of α, as many as ω says
+    Cells,
+    /// α with its axes reversed
+    Transpose,
+    /// A vector of naturals
+    Range,
+    /// Modifiers, whose verbs' types come from their operands'
+    Derived,
+}
+
+/// How `⍜` puts back what a function did
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Under {
+    /// The function only moves elements around, so they can be put back where they came from
+    Structural,
+    /// The function is undone by this primitive, given the argument on this side (and whatever
+    /// was bound to the original on the other one). Only for exact inverses, so `×` and `÷` have
+    /// none: `÷` rounds down, and there are no floats yet
+    Inverse(Primitive, Side),
+}
+
+impl Primitive {
+    pub(crate) fn info(self) -> &'static PrimitiveInfo {
+        PRIMITIVES.iter().find(|i| i.primitive == self).expect("every primitive is in the table")
+    }
+    pub(crate) fn from_glyph(c: char) -> Option<Self> {
+        PRIMITIVES.iter().find(|i| i.glyph == c).map(|i| i.primitive)
+    }
+    pub(crate) fn glyph(self) -> char {
+        self.info().glyph
+    }
+    pub(crate) fn category(self) -> Category {
+        self.info().category
+    }
+    /// `⍜`'s registry. How to put back what this primitive did to an argument on `side`, the
+    /// other one being bound if it's dyadic
+    pub(crate) fn under(self, side: Side) -> Option<Under> {
+        self.info().under.iter().find(|(s, _)| *s == side).map(|&(_, under)| under)
+    }
+    /// The side a monadic primitive takes its argument on
+    pub(crate) fn monadic_side(self) -> Side {
+        match self.category() {
+            Category::Ov => Side::Omega,
+            _ => Side::Alpha,
+        }
+    }
+    /// What kind of verb a modifier makes out of its operand(s)
+    pub(crate) fn derived_category(self, alpha: Option<Category>, omega: Option<Category>) -> Category {
+        match self {
+            // Takes after the function being repeated
+            Primitive::Repeat => match omega {
+                Some(c @ (Category::Av | Category::Ov | Category::Dv)) => c,
+                _ => Category::Av,
+            },
+            Primitive::Table => Category::Dv,
+            Primitive::Evert => match omega {
+                Some(c @ (Category::Av | Category::Ov | Category::Dv)) => c,
+                _ => Category::Av,
+            },
+            Primitive::Jot => match (alpha, omega) {
+                // Binding an argument leaves the other one
+                (Some(Category::A | Category::N), Some(Category::Dv)) => Category::Ov,
+                (Some(Category::Dv), Some(Category::A | Category::N)) => Category::Av,
+                (Some(f @ (Category::Av | Category::Ov)), Some(Category::Av | Category::Ov)) => f,
+                (Some(Category::Av | Category::Ov), Some(Category::Dv))
+                | (Some(Category::Dv), Some(Category::Av | Category::Ov)) => Category::Dv,
+                // Rejected by the type checker
+                _ => Category::Av,
+            },
+            // Takes after the function whose effect is undone
+            Primitive::Under => match alpha {
+                Some(c @ (Category::Av | Category::Ov | Category::Dv)) => c,
+                _ => Category::Av,
+            },
+            _ => unreachable!("{self:?} is not a modifier"),
+        }
+    }
+
+    /// Applies a verb primitive. Monadic ones receive their argument on their own side
+    pub(crate) fn apply(self, alpha: Option<&TNoun>, omega: Option<&TNoun>) -> Result<TNoun, TErrorKind> {
+        use Primitive::*;
+        use arithmetic as ar;
+        match (self, alpha, omega) {
+            (Negate, None, Some(w)) => pervade_monadic(w, &ar::negate),
+            (Not, Some(a), None) => pervade_monadic(a, &ar::not),
+            (Sign, Some(a), None) => pervade_monadic(a, &ar::sign),
+            (AbsoluteValue, Some(a), None) => pervade_monadic(a, &ar::abs),
+            (
+                Add | Subtract | Multiply | Divide | Modulo | Maximum | Minimum,
+                Some(a),
+                Some(w),
+            ) => pervade_dyadic(a, w, &|x, y| ar::binary(self, x, y)),
+            (
+                Equals | NotEquals | LessThan | LessOrEqual | GreaterThan | GreaterOrEqual,
+                Some(a),
+                Some(w),
+            ) => pervade_dyadic(a, w, &|x, y| ar::compare(self, x, y)),
+            (And, Some(a), Some(w)) => pervade_dyadic(a, w, &ar::and),
+            (Left, Some(a), Some(_)) => Ok(a.clone()),
+            (Right, Some(_), Some(w)) => Ok(w.clone()),
+            (Match, Some(a), Some(w)) => Ok(TNoun::nat((a == w) as u64)),
+            (Notmatch, Some(a), Some(w)) => Ok(TNoun::nat((a != w) as u64)),
+            (Tally, Some(a), None) => Ok(TNoun::nat(a.shape().first().copied().unwrap_or(1) as u64)),
+            (Shape, Some(a), None) => Ok(TArray::vector(
+                a.shape().iter().map(|&n| TNoun::nat(n as u64)).collect(),
+            )),
+            (Range, Some(a), None) => match a {
+                TNoun::Atom(TAtom::Nat(TNat(n))) => Ok(TArray::vector((0..*n).map(TNoun::nat).collect())),
+                _ => Err(TErrorKind::Domain),
+            },
+            (Reverse, Some(a), None) => Ok(match a {
+                TNoun::Atom(_) => a.clone(),
+                TNoun::Array(arr) => {
+                    let mut cells = arr.major_cells();
+                    cells.reverse();
+                    TArray::noun(arr.shape.clone(), cells.concat())
+                }
+            }),
+            (Transpose, Some(a), None) => Ok(transpose(a)),
+            (Select, Some(a), Some(w)) => select(a, w),
+            (Pick, Some(a), Some(w)) => pick(a, w),
+            (Take | Drop, Some(a), Some(w)) => {
+                let n = match w {
+                    TNoun::Atom(TAtom::Nat(TNat(n))) => *n as usize,
+                    _ => return Err(TErrorKind::Domain),
+                };
+                let TNoun::Array(arr) = a else {
+                    return Err(TErrorKind::Rank);
+                };
+                let mut cells = arr.major_cells();
+                if n > cells.len() {
+                    return Err(TErrorKind::Index);
+                }
+                let cells = match self {
+                    Take => cells.drain(..n).collect::<Vec<_>>(),
+                    _ => cells.split_off(n),
+                };
+                let mut shape = arr.shape.clone();
+                shape[0] = cells.len() as u32;
+                Ok(TArray::noun(shape, cells.concat()))
+            }
+            // Need floats
+            (Reciprocal | Sqrt, ..) => Err(TErrorKind::Domain),
+            _ => unreachable!("{self:?} called with the wrong arity"),
+        }
+    }
+}
+
+impl Primitive {
+    /// Calls the verb this modifier derived from `l` and `r`
+    pub(crate) fn apply_modifier<'src>(
+        self,
+        ev: &mut Evaluator,
+        span: Sp<'src>,
+        l: Option<&TNoun>,
+        r: Option<&TNoun>,
+        alpha: Option<TNoun>,
+        omega: Option<TNoun>,
+    ) -> TResult<'src, TNoun> {
+        match (self, l, r) {
+            (Primitive::Repeat, Some(cond), Some(f)) => repeat(ev, span, cond, f, alpha, omega),
+            (Primitive::Under, Some(g), Some(f)) => under(ev, span, g, f, alpha.or(omega)),
+            (Primitive::Table, None, Some(f)) => table(ev, span, f, alpha, omega),
+            (Primitive::Jot, Some(l), Some(r)) => match (l.category(), r.category()) {
+                (Category::A, _) => r.call(ev, span, Some(l.clone()), omega),
+                (_, Category::A) => l.call(ev, span, alpha, Some(r.clone())),
+                // A monadic verb on one side preprocesses the argument on that side
+                (Category::Dv, _) => {
+                    let omega = r.call_monadic(ev, span, omega.expect("derived verbs are called with their arguments"))?;
+                    l.call(ev, span, alpha, Some(omega))
+                }
+                (_, Category::Dv) => {
+                    let alpha = l.call_monadic(ev, span, alpha.expect("derived verbs are called with their arguments"))?;
+                    r.call(ev, span, Some(alpha), omega)
+                }
+                _ => {
+                    let x = l.call_monadic(ev, span, alpha.or(omega).expect("derived verbs are called with their arguments"))?;
+                    r.call_monadic(ev, span, x)
+                }
+            },
+            // Leading axis functions act on the trailing one when it's made to lead
+            (Primitive::Evert, None, Some(f)) => {
+                let everted = |x: Option<TNoun>| x.map(|x| transpose(&x));
+                f.call(ev, span, everted(alpha), everted(omega)).map(|r| transpose(&r))
+            }
+            _ => unreachable!("{self:?} is not a modifier"),
+        }
+    }
+}
+
+/// `n⍣f` applies `f` n times. `g⍣f` applies `f` until `g` holds, where `g` gets the previous
+/// and the new value as its alpha and omega or, if monadic, just the new value. A dyadic `f` keeps
+/// its alpha and iterates on its omega. The evaluator's repeat limit caps the second form
+fn repeat<'src>(
+    ev: &mut Evaluator,
+    span: Sp<'src>,
+    cond: &TNoun,
+    f: &TNoun,
+    alpha: Option<TNoun>,
+    omega: Option<TNoun>,
+) -> TResult<'src, TNoun> {
+    let dyadic = f.category() == Category::Dv;
+    let (fixed, mut x) = match dyadic {
+        true => (alpha, omega),
+        false => (None, alpha.or(omega)),
+    };
+    let mut x = x.take().expect("derived verbs are called with their arguments");
+    let step = |ev: &mut Evaluator, x: TNoun| match dyadic {
+        true => f.call(ev, span, fixed.clone(), Some(x)),
+        false => f.call_monadic(ev, span, x),
+    };
+
+    if cond.as_function().is_none() {
+        return match cond {
+            TNoun::Atom(TAtom::Nat(TNat(n))) => (0..*n).try_fold(x, |x, _| step(ev, x)),
+            _ => Err(TError::new(TErrorKind::Domain, span)),
+        };
+    }
+    let mut iterations = 0;
+    loop {
+        if ev.repeat_limit().is_some_and(|limit| iterations >= limit) {
+            return Err(TError::new(TErrorKind::RepeatLimit(iterations), span));
+        }
+        let next = step(ev, x.clone())?;
+        let done = match cond.category() {
+            Category::Dv => cond.call(ev, span, Some(x), Some(next.clone()))?,
+            _ => cond.call_monadic(ev, span, next.clone())?,
+        };
+        if done.truthy(span)? {
+            return Ok(next);
+        }
+        x = next;
+        iterations += 1;
+    }
+}
+
+/// `g⍜f` applies `f` to what `g` gives, and then puts the result back. Structural `g`s have it
+/// put back where it came from, other ones are undone with their inverse. A non-function `f`
+/// replaces what `g` selects
+fn under<'src>(ev: &mut Evaluator, span: Sp<'src>, g: &TNoun, f: &TNoun, x: Option<TNoun>) -> TResult<'src, TNoun> {
+    let x = x.expect("derived verbs are called with their arguments");
+    let (under, bound) = g
+        .as_function()
+        .and_then(|(g, _)| g.under())
+        .ok_or(TError::new(TErrorKind::NoInverse, span))?;
+    let selected = g.call_monadic(ev, span, x.clone())?;
+    let new = match f.as_function() {
+        Some(_) => f.call_monadic(ev, span, selected)?,
+        None => f.clone(),
+    };
+    match under {
+        Under::Inverse(inverse, side) => {
+            let (alpha, omega) = match side {
+                Side::Alpha => (Some(new), bound.cloned()),
+                Side::Omega => (bound.cloned(), Some(new)),
+            };
+            TFunction::Primitive(inverse).call(ev, span, alpha, omega)
+        }
+        Under::Structural => {
+            // Running `g` on the indices of `x` tells where each selected element came from
+            let positions = g.call_monadic(ev, span, indices(&x))?;
+            let (shape, mut data) = match x {
+                TNoun::Atom(_) => (vec![], vec![x]),
+                TNoun::Array(arr) => (arr.shape, arr.data),
+            };
+            let positions = match positions {
+                TNoun::Atom(_) => vec![positions],
+                TNoun::Array(arr) => arr.data,
+            };
+            let values = match new {
+                TNoun::Array(arr) if arr.data.len() == positions.len() => arr.data,
+                TNoun::Array(_) => return Err(TError::new(TErrorKind::Length, span)),
+                atom => vec![atom; positions.len()],
+            };
+            for (p, v) in positions.into_iter().zip(values) {
+                let TNoun::Atom(TAtom::Nat(TNat(p))) = p else {
+                    unreachable!("structural functions only move indices around")
+                };
+                data[p as usize] = v;
+            }
+            Ok(TArray::noun(shape, data))
+        }
+    }
+}
+
+/// `f` between every major cell of `alpha` and every one of `omega`. The result's shape is
+/// both arguments' lengths followed by that of what `f` gives
+fn table<'src>(
+    ev: &mut Evaluator,
+    span: Sp<'src>,
+    f: &TNoun,
+    alpha: Option<TNoun>,
+    omega: Option<TNoun>,
+) -> TResult<'src, TNoun> {
+    let (alpha, omega) = alpha.zip(omega).expect("derived verbs are called with their arguments");
+    let (alpha_frame, alpha_cells) = frame_and_cells(alpha);
+    let (omega_frame, omega_cells) = frame_and_cells(omega);
+    let mut cell_shape = None;
+    let mut data = vec![];
+    for a in &alpha_cells {
+        for w in &omega_cells {
+            let r = f.call(ev, span, Some(a.clone()), Some(w.clone()))?;
+            if *cell_shape.get_or_insert_with(|| r.shape().to_vec()) != r.shape() {
+                return Err(TError::new(TErrorKind::Length, span));
+            }
+            match r {
+                TNoun::Atom(_) => data.push(r),
+                TNoun::Array(arr) => data.extend(arr.data),
+            }
+        }
+    }
+    let shape = [alpha_frame, omega_frame, cell_shape.unwrap_or_default()].concat();
+    Ok(TArray::noun(shape, data))
+}
+
+/// An array with `cells` as its major cells, which must all have the same shape
+pub(crate) fn stack(cells: Vec<TNoun>) -> Result<TNoun, TErrorKind> {
+    let cell_shape = cells.first().map(|c| c.shape().to_vec()).unwrap_or_default();
+    if let Some(c) = cells.iter().find(|c| c.shape() != cell_shape) {
+        return Err(match c.shape().len() == cell_shape.len() {
+            true => TErrorKind::Length,
+            false => TErrorKind::Rank,
+        });
+    }
+    let shape = [vec![cells.len() as u32], cell_shape].concat();
+    let data = cells
+        .into_iter()
+        .flat_map(|c| match c {
+            TNoun::Atom(_) => vec![c],
+            TNoun::Array(arr) => arr.data,
+        })
+        .collect();
+    Ok(TArray::noun(shape, data))
+}
+
+/// The major cells of `x`, and the axis they make up. Scalars are their only cell, with no axis
+pub(crate) fn frame_and_cells(x: TNoun) -> (Shape, Vec<TNoun>) {
+    match x {
+        TNoun::Atom(_) => (vec![], vec![x]),
+        TNoun::Array(arr) => {
+            let cell_shape = &arr.shape[1..];
+            let cells = arr
+                .major_cells()
+                .into_iter()
+                .map(|data| TArray::noun(cell_shape.to_vec(), data))
+                .collect();
+            (vec![arr.shape[0]], cells)
+        }
+    }
+}
+
+/// An array shaped like `x`, holding the flat index of each element
+fn indices(x: &TNoun) -> TNoun {
+    let shape = x.shape().to_vec();
+    let n = shape.iter().product::<u32>() as u64;
+    TArray::noun(shape, (0..n).map(TNoun::nat).collect())
+}
+
+fn transpose(x: &TNoun) -> TNoun {
+    let TNoun::Array(arr) = x else {
+        return x.clone();
+    };
+    let shape: Shape = arr.shape.iter().rev().copied().collect();
+    let strides = |sh: &[u32]| {
+        let mut acc = 1;
+        let mut st: Vec<usize> = sh
+            .iter()
+            .rev()
+            .map(|&n| {
+                let s = acc;
+                acc *= n as usize;
+                s
+            })
+            .collect();
+        st.reverse();
+        st
+    };
+    let old = strides(&arr.shape);
+    let new = strides(&shape);
+    let data = (0..arr.data.len())
+        .map(|i| {
+            // The new index's axes are the old one's, reversed
+            let src: usize = new
+                .iter()
+                .zip(&shape)
+                .zip(old.iter().rev())
+                .map(|((s, n), o)| i / s % *n as usize * o)
+                .sum();
+            arr.data[src].clone()
+        })
+        .collect();
+    TArray::noun(shape, data)
+}
+
+fn index(n: &TNoun, len: u32) -> Result<usize, TErrorKind> {
+    match n {
+        TNoun::Atom(TAtom::Nat(TNat(i))) if *i < len as u64 => Ok(*i as usize),
+        TNoun::Atom(TAtom::Nat(_)) => Err(TErrorKind::Index),
+        _ => Err(TErrorKind::Domain),
+    }
+}
+
+/// The major cells at each of `w`'s indices
+fn select(a: &TNoun, w: &TNoun) -> Result<TNoun, TErrorKind> {
+    let TNoun::Array(arr) = a else {
+        return Err(TErrorKind::Rank);
+    };
+    let cells = arr.major_cells();
+    let cell_shape = &arr.shape[1..];
+    let (outer, ixs) = match w {
+        TNoun::Atom(_) => (vec![], vec![w.clone()]),
+        TNoun::Array(ws) => (ws.shape.clone(), ws.data.clone()),
+    };
+    let data = ixs
+        .iter()
+        .map(|i| index(i, arr.shape[0]).map(|i| cells[i].clone()))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(TArray::noun([outer, cell_shape.to_vec()].concat(), data.concat()))
+}
+
+/// The element at the index vector `w`, which may be a scalar for vectors
+fn pick(a: &TNoun, w: &TNoun) -> Result<TNoun, TErrorKind> {
+    let ixs = match w {
+        TNoun::Atom(_) => vec![w.clone()],
+        TNoun::Array(ws) if ws.shape.len() == 1 => ws.data.clone(),
+        TNoun::Array(_) => return Err(TErrorKind::Rank),
+    };
+    if ixs.len() != a.shape().len() {
+        return Err(TErrorKind::Rank);
+    }
+    let TNoun::Array(arr) = a else {
+        return Ok(a.clone());
+    };
+    let flat = ixs
+        .iter()
+        .zip(&arr.shape)
+        .try_fold(0, |acc, (i, &n)| Ok(acc * n as usize + index(i, n)?))?;
+    Ok(arr.data[flat].clone())
+}
+
+impl TArray {
+    /// The data of each major cell
+    pub(crate) fn major_cells(&self) -> Vec<Vec<TNoun>> {
+        let n = self.shape[0] as usize;
+        // Taken from the shape, as cells of an array like `[0⇡ ⋄ 0⇡]` hold no data to divide up
+        match self.shape[1..].iter().product::<u32>() as usize {
+            0 => vec![vec![]; n],
+            size => self.data.chunks(size).map(<[_]>::to_vec).collect(),
+        }
+    }
+}
+
+fn pervade_monadic(
+    x: &TNoun,
+    f: &dyn Fn(&TAtom) -> Result<TAtom, TErrorKind>,
+) -> Result<TNoun, TErrorKind> {
+    match x {
+        TNoun::Atom(a) => f(a).map(TNoun::Atom),
+        TNoun::Array(arr) => Ok(TNoun::Array(TArray {
+            shape: arr.shape.clone(),
+            data: arr
+                .data
+                .iter()
+                .map(|x| pervade_monadic(x, f))
+                .collect::<Result<_, _>>()?,
+        })),
+    }
+}
+
+fn pervade_dyadic(
+    a: &TNoun,
+    w: &TNoun,
+    f: &dyn Fn(&TAtom, &TAtom) -> Result<TAtom, TErrorKind>,
+) -> Result<TNoun, TErrorKind> {
+    match (a, w) {
+        (TNoun::Atom(a), TNoun::Atom(w)) => f(a, w).map(TNoun::Atom),
+        (TNoun::Atom(_), TNoun::Array(arr)) => Ok(TNoun::Array(TArray {
+            shape: arr.shape.clone(),
+            data: arr
+                .data
+                .iter()
+                .map(|w| pervade_dyadic(a, w, f))
+                .collect::<Result<_, _>>()?,
+        })),
+        (TNoun::Array(arr), TNoun::Atom(_)) => Ok(TNoun::Array(TArray {
+            shape: arr.shape.clone(),
+            data: arr
+                .data
+                .iter()
+                .map(|a| pervade_dyadic(a, w, f))
+                .collect::<Result<_, _>>()?,
+        })),
+        (TNoun::Array(l), TNoun::Array(r)) => {
+            if l.shape.len() != r.shape.len() {
+                return Err(TErrorKind::Rank);
+            }
+            if l.shape != r.shape {
+                return Err(TErrorKind::Length);
+            }
+            Ok(TNoun::Array(TArray {
+                shape: l.shape.clone(),
+                data: l
+                    .data
+                    .iter()
+                    .zip(&r.data)
+                    .map(|(a, w)| pervade_dyadic(a, w, f))
+                    .collect::<Result<_, _>>()?,
+            }))
+        }
+    }
+}
+
+pub(crate) mod arithmetic {
     use crate::*;
 
-    fn negate<'src>(a: TArray) -> TResult<'src, TArray> {
-        todo!()
+    /// Numbers are computed on as i128s and then narrowed back into their type
+    fn num(a: &TAtom) -> Option<(TAtomKind, i128)> {
+        Some(match a {
+            TAtom::Nat(TNat(n)) => (TAtomKind::Nat, *n as i128),
+            TAtom::Int(TInt(n)) => (TAtomKind::Int, *n as i128),
+            TAtom::U8(TU8(n)) => (TAtomKind::U8, *n as i128),
+            TAtom::U16(TU16(n)) => (TAtomKind::U16, *n as i128),
+            TAtom::I8(TI8(n)) => (TAtomKind::I8, *n as i128),
+            TAtom::I16(TI16(n)) => (TAtomKind::I16, *n as i128),
+            _ => return None,
+        })
+    }
+    fn make(kind: TAtomKind, n: i128) -> Result<TAtom, TErrorKind> {
+        let of = |_| TErrorKind::Overflow;
+        Ok(match kind {
+            TAtomKind::Nat => TAtom::Nat(TNat(n.try_into().map_err(of)?)),
+            TAtomKind::Int => TAtom::Int(TInt(n.try_into().map_err(of)?)),
+            TAtomKind::U8 => TAtom::U8(TU8(n.try_into().map_err(of)?)),
+            TAtomKind::U16 => TAtom::U16(TU16(n.try_into().map_err(of)?)),
+            TAtomKind::I8 => TAtom::I8(TI8(n.try_into().map_err(of)?)),
+            TAtomKind::I16 => TAtom::I16(TI16(n.try_into().map_err(of)?)),
+            _ => return Err(TErrorKind::Domain),
+        })
+    }
+
+    /// The type both arguments of an arithmetic function get converted to.
+    /// Nats adapt to the other side, as that's what number literals are
+    pub(crate) fn promote(a: TAtomKind, b: TAtomKind) -> Option<TAtomKind> {
+        use TAtomKind::*;
+        let numeric = |k| matches!(k, Nat | Int | U8 | U16 | I8 | I16);
+        if !numeric(a) || !numeric(b) {
+            return None;
+        }
+        Some(match (a, b) {
+            (a, b) if a == b => a,
+            (Nat, x) | (x, Nat) => x,
+            (U8, U16) | (U16, U8) => U16,
+            (I8, I16) | (I16, I8) => I16,
+            _ => Int,
+        })
+    }
+    /// The type that can hold the negation of any value of `k`
+    pub(crate) fn signed(k: TAtomKind) -> Option<TAtomKind> {
+        use TAtomKind::*;
+        match k {
+            Nat | U16 | Int => Some(Int),
+            U8 | I16 => Some(I16),
+            I8 => Some(I8),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn negate(a: &TAtom) -> Result<TAtom, TErrorKind> {
+        let (k, n) = num(a).ok_or(TErrorKind::Domain)?;
+        make(signed(k).ok_or(TErrorKind::Domain)?, -n)
+    }
+    pub(crate) fn not(a: &TAtom) -> Result<TAtom, TErrorKind> {
+        match num(a) {
+            Some((k, n @ (0 | 1))) => make(k, 1 - n),
+            _ => Err(TErrorKind::Domain),
+        }
     }
-    fn plus<'src>(a: TArray, b: TArray) -> TResult<'src, TArray> {
-        todo!()
+    pub(crate) fn and(a: &TAtom, b: &TAtom) -> Result<TAtom, TErrorKind> {
+        match (num(a), num(b)) {
+            (Some((_, a @ (0 | 1))), Some((_, b @ (0 | 1)))) => Ok(TAtom::Nat(TNat((a & b) as u64))),
+            _ => Err(TErrorKind::Domain),
+        }
     }
-    fn minus<'src>(a: TArray, b: TArray) -> TResult<'src, TArray> {
-        todo!()
+    pub(crate) fn sign(a: &TAtom) -> Result<TAtom, TErrorKind> {
+        let (k, n) = num(a).ok_or(TErrorKind::Domain)?;
+        make(signed(k).ok_or(TErrorKind::Domain)?, n.signum())
     }
-    fn times<'src>(a: TArray, b: TArray) -> TResult<'src, TArray> {
-        todo!()
+    pub(crate) fn abs(a: &TAtom) -> Result<TAtom, TErrorKind> {
+        let (k, n) = num(a).ok_or(TErrorKind::Domain)?;
+        make(k, n.abs())
+    }
+
+    pub(crate) fn binary(p: Primitive, a: &TAtom, b: &TAtom) -> Result<TAtom, TErrorKind> {
+        let ((ka, a), (kb, b)) = (num(a).ok_or(TErrorKind::Domain)?, num(b).ok_or(TErrorKind::Domain)?);
+        let k = promote(ka, kb).ok_or(TErrorKind::Domain)?;
+        let n = match p {
+            Primitive::Add => a + b,
+            Primitive::Subtract => return make(signed(k).ok_or(TErrorKind::Domain)?, a - b),
+            Primitive::Multiply => a * b,
+            Primitive::Divide if b == 0 => return Err(TErrorKind::Domain),
+            Primitive::Divide => a.div_euclid(b),
+            Primitive::Modulo if b == 0 => return Err(TErrorKind::Domain),
+            Primitive::Modulo => a.rem_euclid(b),
+            Primitive::Maximum => a.max(b),
+            Primitive::Minimum => a.min(b),
+            _ => unreachable!("{p:?} is not arithmetic"),
+        };
+        make(k, n)
     }
-    fn divide<'src>(a: TArray, b: TArray) -> TResult<'src, TArray> {
-        todo!()
+
+    pub(crate) fn compare(p: Primitive, a: &TAtom, b: &TAtom) -> Result<TAtom, TErrorKind> {
+        let ord = match (a, b) {
+            (TAtom::Char(TChar(a)), TAtom::Char(TChar(b))) => a.cmp(b),
+            _ => match (num(a), num(b)) {
+                (Some((_, a)), Some((_, b))) => a.cmp(&b),
+                _ if matches!(p, Primitive::Equals) => return Ok(TAtom::Nat(TNat(0))),
+                _ if matches!(p, Primitive::NotEquals) => return Ok(TAtom::Nat(TNat(1))),
+                _ => return Err(TErrorKind::Domain),
+            },
+        };
+        let b = match p {
+            Primitive::Equals => ord.is_eq(),
+            Primitive::NotEquals => ord.is_ne(),
+            Primitive::LessThan => ord.is_lt(),
+            Primitive::LessOrEqual => ord.is_le(),
+            Primitive::GreaterThan => ord.is_gt(),
+            Primitive::GreaterOrEqual => ord.is_ge(),
+            _ => unreachable!("{p:?} is not a comparison"),
+        };
+        Ok(TAtom::Nat(TNat(b as u64)))
+    }
+}
+
+#[test]
+fn readme() {
+    let readme = include_str!("../README.org");
+    let (_, list) = readme.split_once("** List of primitives").expect("the README lists the primitives");
+    let rows = list
+        .lines()
+        .take_while(|l| !l.starts_with("**"))
+        .filter(|l| l.starts_with('|') && !l.starts_with("|-"))
+        .map(|l| l.split('|').map(str::trim).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    for info in PRIMITIVES {
+        let glyph = crate::glyphs::org_glyph(info.glyph);
+        let (kind, arity) = match info.category {
+            Category::Av | Category::Ov => ("Function", "Monadic"),
+            Category::Dv => ("Function", "Dyadic"),
+            Category::Aa | Category::Oa => ("Modifier", "Monadic"),
+            _ => ("Modifier", "Dyadic"),
+        };
+        let found = rows.iter().filter(|r| r[1] == glyph).collect::<Vec<_>>();
+        assert!(!found.is_empty(), "the README doesn't list {glyph}");
+        for row in found {
+            assert_eq!(&row[3..6], [info.name, kind, arity], "the README's row for {glyph}");
+        }
     }
 }
@@ -0,0 +1,348 @@
+//! `tqo repl`: reads statements a line at a time and shows the value and type of each expression.
+//! Bindings are kept from one input to the next, and input with brackets left open carries on over
+//! the following lines until they're closed. Lines starting with `)` are commands, see
+//! [`Session::command`]. Files being watched are loaded again, if they changed, before each input.
+//!
+//! Glyphs are typed as a backtick then a key, e.g. `` `r `` for `⍴`, or as `\name` then Tab, e.g.
+//! `\under` for `⍜`. `)glyphs` lists them all, see [`tqo::glyphs`]
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+    process::ExitCode,
+    time::SystemTime,
+};
+
+use rustyline::{
+    Cmd, ConditionalEventHandler, Context, Editor, Event, EventContext, EventHandler, KeyCode, KeyEvent, Modifiers,
+    Movement, RepeatCount,
+    completion::{Completer, Pair},
+    error::ReadlineError,
+    highlight::Highlighter,
+    hint::Hinter,
+    history::FileHistory,
+    validate::Validator,
+};
+use tqo::{Evaluator, Severity};
+
+/// Input is indented, as in other APLs, so that it stands out from the output below it
+const PROMPT: &str = "      ";
+const CONTINUATION: &str = "    ⋮ ";
+
+/// Where the history is kept between sessions, `~/.tqo_history` unless `TQO_HISTORY` says otherwise
+fn history_file() -> Option<PathBuf> {
+    match env::var_os("TQO_HISTORY") {
+        Some(path) => Some(path.into()),
+        None => env::var_os("HOME").map(|home| PathBuf::from(home).join(".tqo_history")),
+    }
+}
+
+type LineEditor = Editor<Glyphs, FileHistory>;
+
+pub fn repl(args: &[String], repeat_limit: usize) -> ExitCode {
+    let mut editor = match LineEditor::new() {
+        Ok(editor) => editor,
+        Err(e) => {
+            eprintln!("tqo: can't start the repl: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    editor.set_helper(Some(Glyphs));
+    editor.bind_sequence(Event::Any, EventHandler::Conditional(Box::new(Glyphs)));
+    let history = history_file();
+    if let Some(path) = &history {
+        // There's none the first time
+        let _ = editor.load_history(path);
+    }
+    let mut session = Session {
+        evaluator: Evaluator::new().with_args(args).with_repeat_limit(repeat_limit),
+        watched: vec![],
+    };
+    while let Some(input) = read(&mut editor) {
+        session.poll();
+        if input.trim().is_empty() {
+            continue;
+        }
+        let _ = editor.add_history_entry(input.as_str());
+        if let Some(cmd) = input.trim_start().strip_prefix(')') {
+            session.command(cmd);
+            continue;
+        }
+        match session.evaluator.run_shown(&input) {
+            Ok(Some((value, ty))) => {
+                println!("{value}");
+                println!("⍝ {ty}");
+            }
+            Ok(None) => {}
+            Err(e) => eprint!("{}", e.diagnostic().render(&input, None)),
+        }
+    }
+    for watched in session.watched.iter().filter(|w| w.temporary) {
+        let _ = fs::remove_file(&watched.path);
+    }
+    if let Some(path) = &history
+        && let Err(e) = editor.save_history(path)
+    {
+        eprintln!("tqo: can't save the history to {}: {e}", path.display());
+    }
+    ExitCode::SUCCESS
+}
+
+const COMMANDS: &str = "commands:
+  )vars             the names bound to arrays
+  )fns              the names bound to functions
+  )erase NAME...    unbinds the names
+  )type EXPR        the type EXPR would have, without running it
+  )save FILE        writes every binding still in effect to FILE
+  )load FILE        runs FILE, keeping what it binds
+  )ed NAME          edits NAME's definition in $EDITOR, runs it once saved and watches it
+  )watch [FILE]     loads FILE again whenever it changes, or lists the files being watched
+  )unwatch FILE     stops watching FILE
+  )help GLYPH       what GLYPH does, which may also be given by name
+  )glyphs           how to type each glyph, as a backtick then a key or as \\name then Tab";
+
+struct Session {
+    evaluator: Evaluator,
+    /// Files loaded again whenever they change, see [`Session::poll`]
+    watched: Vec<Watched>,
+}
+
+struct Watched {
+    path: PathBuf,
+    /// When it was last loaded
+    modified: Option<SystemTime>,
+    /// Made by `)ed`, and so to be removed at the end
+    temporary: bool,
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+impl Session {
+    /// Runs `)cmd`. Errors are reported and leave the bindings as they were
+    fn command(&mut self, cmd: &str) {
+        let (name, rest) = cmd.split_once(char::is_whitespace).unwrap_or((cmd, ""));
+        let rest = rest.trim();
+        match (name, rest) {
+            ("vars", "") => println!("{}", self.evaluator.vars().join(" ")),
+            ("fns", "") => println!("{}", self.evaluator.fns().join(" ")),
+            ("erase", names) if !names.is_empty() => {
+                for name in names.split_whitespace() {
+                    if !self.evaluator.erase(name) {
+                        eprintln!("{name} isn't bound");
+                    }
+                }
+            }
+            ("type", src) if !src.is_empty() => match self.evaluator.type_of(src) {
+                Ok(Some(ty)) => println!("{ty}"),
+                Ok(None) => {}
+                Err(e) => eprint!("{}", e.diagnostic().render(src, None)),
+            },
+            ("save", file) if !file.is_empty() => {
+                if let Err(e) = fs::write(file, self.evaluator.workspace()) {
+                    eprintln!("can't write {file}: {e}");
+                }
+            }
+            ("load", file) if !file.is_empty() => {
+                self.load(Path::new(file));
+            }
+            ("ed", name) if !name.is_empty() && !name.contains(char::is_whitespace) => self.edit(name),
+            ("watch", "") => {
+                for watched in &self.watched {
+                    println!("{}", watched.path.display());
+                }
+            }
+            ("watch", file) => {
+                let path = PathBuf::from(file);
+                self.load(&path);
+                self.watch(path, false);
+            }
+            ("unwatch", file) if !file.is_empty() => {
+                let before = self.watched.len();
+                self.watched.retain(|w| w.path != Path::new(file));
+                if self.watched.len() == before {
+                    eprintln!("{file} isn't being watched");
+                }
+            }
+            ("glyphs", "") => {
+                for g in tqo::glyphs::glyphs() {
+                    let key = g.key.map(|k| format!("`{k}")).unwrap_or_default();
+                    println!("{}  {key:2}  \\{:12} {}", g.glyph, g.name, g.meaning);
+                }
+            }
+            ("help", word) if !word.is_empty() => match tqo::glyphs::help(word) {
+                Some(help) => {
+                    println!("{help}");
+                    // The help starts with the glyph, whose type is worth showing if it's a verb
+                    let glyph = help.chars().take(1).collect::<String>();
+                    if let Ok(Some(ty)) = self.evaluator.type_of(&glyph) {
+                        println!("{ty}");
+                    }
+                }
+                None => eprintln!("there's no glyph {word}, see )glyphs"),
+            },
+            _ => eprintln!("{COMMANDS}"),
+        }
+    }
+
+    /// Runs `path`, keeping what it binds only if it all runs, and warns about the bindings that
+    /// read what it binds and no longer type check. Gives back whether it ran
+    fn load(&mut self, path: &Path) -> bool {
+        let file = path.display().to_string();
+        let src = match fs::read_to_string(path) {
+            Ok(src) => src,
+            Err(e) => {
+                eprintln!("can't read {file}: {e}");
+                return false;
+            }
+        };
+        let dependents = match self.evaluator.reload(&src) {
+            Ok(dependents) => dependents,
+            Err(e) => {
+                eprint!("{}", e.diagnostic().render(&src, Some(&file)));
+                return false;
+            }
+        };
+        for src in dependents {
+            if let Err(e) = self.evaluator.type_of(&src) {
+                let mut d = e.diagnostic();
+                d.severity = Severity::Warning;
+                d = d.with_note(format!("it type checked against what {file} bound before"));
+                eprint!("{}", d.render(&src, Some("<workspace>")));
+            }
+        }
+        true
+    }
+
+    fn watch(&mut self, path: PathBuf, temporary: bool) {
+        let modified = modified(&path);
+        match self.watched.iter_mut().find(|w| w.path == path) {
+            Some(watched) => watched.modified = modified,
+            None => self.watched.push(Watched {
+                path,
+                modified,
+                temporary,
+            }),
+        }
+    }
+
+    /// Loads the watched files that changed since they were last loaded. A file that fails to load
+    /// isn't tried again until it changes again
+    fn poll(&mut self) {
+        for i in 0..self.watched.len() {
+            let modified = modified(&self.watched[i].path);
+            if modified.is_some() && modified != self.watched[i].modified {
+                self.watched[i].modified = modified;
+                let path = self.watched[i].path.clone();
+                if self.load(&path) {
+                    println!("⍝ reloaded {}", path.display());
+                }
+            }
+        }
+    }
+
+    /// Opens `name`'s definition, or a new dfn if it has none, in `$VISUAL` or `$EDITOR`. The file
+    /// is run once the editor exits, and again whenever it's saved after that, for editors that
+    /// go on in the background
+    fn edit(&mut self, name: &str) {
+        let src = match self.evaluator.definition(name) {
+            Some(src) => format!("{src}\n"),
+            None => format!("{name} ← {{ω}}\n"),
+        };
+        let path = env::temp_dir().join(format!("tqo-{}-{name}.tqo", process::id()));
+        if let Err(e) = fs::write(&path, &src) {
+            eprintln!("can't write {}: {e}", path.display());
+            return;
+        }
+        let editor = env::var("VISUAL").or_else(|_| env::var("EDITOR")).unwrap_or_else(|_| "vi".into());
+        // The editor may be given with arguments of its own, e.g. `emacsclient -n`
+        let mut words = editor.split_whitespace();
+        let status = process::Command::new(words.next().unwrap_or("vi")).args(words).arg(&path).status();
+        match status {
+            Ok(status) if status.success() => {
+                self.load(&path);
+                self.watch(path, true);
+            }
+            Ok(status) => {
+                eprintln!("{editor} exited with {status}, so {name} is left as it was");
+                let _ = fs::remove_file(&path);
+            }
+            Err(e) => {
+                eprintln!("can't run {editor}: {e}");
+                let _ = fs::remove_file(&path);
+            }
+        }
+    }
+}
+
+/// The next input, which is several lines long if brackets are left open. `None` once there's no
+/// more of it. Ctrl-C throws away what's been typed so far
+fn read(editor: &mut LineEditor) -> Option<String> {
+    let mut input = String::new();
+    loop {
+        let prompt = if input.is_empty() { PROMPT } else { CONTINUATION };
+        match editor.readline(prompt) {
+            Ok(line) => {
+                if !input.is_empty() {
+                    input.push('\n');
+                }
+                input.push_str(&line);
+                if !Evaluator::incomplete(&input) {
+                    return Some(input);
+                }
+            }
+            Err(ReadlineError::Interrupted) => input.clear(),
+            Err(ReadlineError::Eof) if !input.is_empty() => return Some(input),
+            Err(_) => return None,
+        }
+    }
+}
+
+/// Types glyphs: a backtick then a key is replaced by the glyph as the key is pressed, and
+/// `\name` is completed to the glyph with Tab
+struct Glyphs;
+
+impl ConditionalEventHandler for Glyphs {
+    fn handle(&self, evt: &Event, _: RepeatCount, _: bool, ctx: &EventContext) -> Option<Cmd> {
+        let Some(&KeyEvent(KeyCode::Char(key), Modifiers::NONE)) = evt.get(0) else {
+            return None;
+        };
+        if !ctx.line()[..ctx.pos()].ends_with('`') {
+            return None;
+        }
+        let glyph = tqo::glyphs::by_key(key)?;
+        Some(Cmd::Replace(Movement::BackwardChar(1), Some(glyph.to_string())))
+    }
+}
+
+impl Completer for Glyphs {
+    type Candidate = Pair;
+
+    fn complete(&self, line: &str, pos: usize, _: &Context<'_>) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let before = &line[..pos];
+        let Some(start) = before.rfind('\\') else {
+            return Ok((pos, vec![]));
+        };
+        let name = &before[start + 1..];
+        if !name.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Ok((pos, vec![]));
+        }
+        let found = tqo::glyphs::by_name(name).into_iter().map(|g| Pair {
+            display: format!("{} {}", g.glyph, g.meaning),
+            replacement: g.glyph.to_string(),
+        });
+        Ok((start, found.collect()))
+    }
+}
+
+impl Hinter for Glyphs {
+    type Hint = String;
+}
+
+impl Highlighter for Glyphs {}
+
+impl Validator for Glyphs {}
+
+impl rustyline::Helper for Glyphs {}
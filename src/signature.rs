@@ -0,0 +1,52 @@
+//! Type signatures, written above what they describe as `: ∀T '(⍤R, T) » Name ↳ '(⍤R, T)`. The
+//! names after `∀` are type variables, which each call site instantiates anew. They may be
+//! constrained to a class of scalar types, as in `∀T (Num T)`. What the output is may be relative
+//! to the arguments, as in `↳ '(⍤-1, T)`
+
+use crate::Side;
+
+/// A signature as written, before the names in it are looked up
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SignatureSyntax {
+    pub(crate) name: String,
+    /// What follows `∀`
+    pub(crate) vars: Vec<String>,
+    /// `(Num T)`, as the class then the variable
+    pub(crate) constraints: Vec<(String, String)>,
+    /// Left of `»`
+    pub(crate) alpha: Option<TypeSyntax>,
+    /// Right of `«`
+    pub(crate) omega: Option<TypeSyntax>,
+    /// Right of `↳`
+    pub(crate) output: TypeSyntax,
+}
+
+/// `'Scalar`, which is a scalar, or `'(⍤R, ≢=5, ⍴S, Scalar)`, each of the axis descriptions being
+/// optional. A bare number is a rank, as in `'(1, Char)`, and axes may be named, as in
+/// `'(⍴[rows, cols=3], Nat)`
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub(crate) struct TypeSyntax {
+    pub(crate) scalar: String,
+    pub(crate) rank: Option<Bound>,
+    pub(crate) length: Option<Bound>,
+    pub(crate) shape: Option<Bound>,
+}
+
+/// What follows `⍤`, `≢` or `⍴`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Bound {
+    /// A type variable, e.g. `≢N`
+    Var(String),
+    /// `=5`
+    Nat(u32),
+    /// `=2_3`
+    Shape(Vec<u32>),
+    /// `[rows=3, cols=?]`, where the names and sizes are both optional
+    Axes(Vec<(Option<String>, Option<u32>)>),
+    /// `⍤₁`, that of ⍺ (`₁`) or ω (`₂`). Left unsaid before `-1` or `⇌` when there's a single argument
+    Input(Option<Side>),
+    /// `⍤-1` or `≢N+2`
+    Offset(Box<Bound>, i32),
+    /// `⍴⇌` or `⍴S⇌`
+    Reversed(Box<Bound>),
+}
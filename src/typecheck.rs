@@ -1,71 +1,2929 @@
-use crate::*;
+use std::rc::Rc;
 
-struct TypeCheckErr<'src> {
-    span: Sp<'src>,
-    kind: TypeCheckErrKind,
+use crate::{
+    data::{DataSyntax, FieldType, Variant},
+    primitive::Rule,
+    signature::{Bound, SignatureSyntax, TypeSyntax},
+    *,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeCheckErr<'src> {
+    pub(crate) span: Sp<'src>,
+    pub(crate) kind: TypeCheckErrKind,
 }
-enum TypeCheckErrKind {
-    InternalParsingErr,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeCheckErrKind {
+    UnknownName,
+    ScalarMismatch { expected: TAtomKind, found: TAtomKind },
+    /// Arithmetic on something that isn't a number
+    NotNumeric(TAtomKind),
+    RankMismatch { expected: u32, found: u32 },
+    LengthMismatch { expected: u32, found: u32 },
+    ShapeMismatch { expected: Shape, found: Shape },
+    /// An array where a verb was needed, or vice versa
+    NotAVerb,
+    NotANoun,
+    /// `⍣`'s count must be a scalar Nat
+    RepeatCount,
+    /// `⍣`'s function must give back what it's given, so it can be fed its own output
+    RepeatNotClosed { input: Box<Known>, output: Box<Known> },
+    /// A dyadic verb where only monadic ones make sense
+    NotMonadic,
+    /// A monadic verb where only dyadic ones make sense
+    NotDyadic,
+    /// `⍜`'s function has no inverse, nor is it structural
+    NoInverse,
+    /// A field declared with a type that's neither built in nor declared before
+    UnknownType(String),
+    /// A field that no variant of the value's type has, or a field of something that isn't a value
+    /// of a user-defined type
+    NoField(String),
+    /// A `⊔` pattern naming a variant its value's type doesn't have
+    UnknownVariant(String),
+    /// A `⊔` that doesn't cover these variants, or `_` when its value isn't of a user-defined type
+    NonExhaustive(Vec<String>),
+    /// Two arrays whose axes are named differently, even if their sizes agree
+    AxisMismatch { expected: Box<Known>, found: Box<Known> },
+    /// The arguments of a call would have a type variable stand for two different things
+    Conflict {
+        var: String,
+        first: Box<TypeLevelVal>,
+        second: Box<TypeLevelVal>,
+    },
+    /// A signature with `⍤-1` or the like in an argument's type, or in its output when it's unclear
+    /// which argument it's relative to
+    BadRelative,
+    /// A signature constraining a variable to a class other than `Eq`, `Ord` or `Num`
+    UnknownClass(String),
+    /// A call where a constrained type variable would stand for something outside its class
+    NoInstance { class: Class, found: TypeLevelVal },
+    /// A binding whose body gives something other than what its signature says
+    Unsatisfied { declared: Box<Known>, found: Box<Known> },
+    /// A function bound without a signature when they're required, along with the one it could
+    /// be given if its body says enough to write one
+    NoSignature { name: String, inferred: Option<String> },
+    /// A signature followed by something other than the binding it describes
+    Unbound(String),
 }
 
-pub fn typecheck_token<'src>(
-    s: &'src str,
-    t: ExprToken<'src>,
-) -> Result<TType, TypeCheckErr<'src>> {
-    use InputTypeSpecifier as ITS;
-    use OutputTypeSpecifier as OTS;
-    match t.kind {
-        ExprTokenKind::Number | ExprTokenKind::Char => Ok(TType {
-            alpha: None,
-            omega: None,
-            output: Some(OTS {
-                rank: Some(Box::new(|_, _| 0)),
-                length: Some(Box::new(|_, _| 1)),
-                shape: Some(Box::new(|_, _| vec![])),
+/// What is statically known about an array
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Known {
+    scalar: Option<TAtomKind>,
+    rank: Option<u32>,
+    length: Option<u32>,
+    shape: Option<Shape>,
+    /// The names of the axes, `None` for those without one. Only there if some axis has a name
+    axes: Option<Axes>,
+}
+
+type Axes = Vec<Option<String>>;
+
+/// Values of [`TypeLevelExpr`]s
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeLevelVal {
+    Nat(u32),
+    Shape(Shape),
+    Scalar(TAtomKind),
+    Axes(Axes),
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TypeEnv {
+    names: HashMap<Ident, TType>,
+    /// Every `□` declaration so far, indexed by [`DataId`]
+    data: Vec<Rc<DataDecl>>,
+    /// The types given by `:` signatures, which their names take once they're bound
+    signatures: HashMap<Ident, TType>,
+    /// How many instances of type variables have been made, see [`TypeEnv::instantiate`]
+    instances: u32,
+    /// Whether functions must be given a signature, which dfns' locals needn't be
+    strict: bool,
+    /// The dfns bound to names without a signature, whose bodies are checked again with the
+    /// arguments of each call
+    dfns: HashMap<Ident, Rc<BoundDfn>>,
+    /// The names of the dfns being checked at a call, which aren't checked again when they call
+    /// themselves
+    calling: Vec<Ident>,
+}
+
+/// A dfn bound to a name, see [`TypeEnv::dfns`]
+#[derive(Debug)]
+struct BoundDfn {
+    body: Vec<ExprTree<'static>>,
+    depth: usize,
+    /// What it was written in, which its spans refer to
+    src: Rc<str>,
+}
+
+/// What a bound of a signature stands for. `of` gives what an argument has of it, e.g. its rank,
+/// and `input` which argument a relative bound is relative to
+fn bound_expr(
+    b: &Bound,
+    of: fn(Side) -> TypeLevelExpr,
+    input: &dyn Fn(Option<Side>) -> Result<Side, TypeCheckErrKind>,
+    var: &dyn Fn(&str) -> Result<TypeLevelExpr, TypeCheckErrKind>,
+) -> Result<TypeLevelExpr, TypeCheckErrKind> {
+    use TypeLevelExpr as E;
+    Ok(match b {
+        Bound::Var(v) => var(v)?,
+        Bound::Nat(n) => E::Nat(*n),
+        Bound::Shape(sh) => E::Shape(sh.clone()),
+        Bound::Axes(_) => unreachable!("only shapes are written axis by axis"),
+        Bound::Input(side) => of(input(*side)?),
+        Bound::Offset(b, n) => {
+            let b = Box::new(bound_expr(b, of, input, var)?);
+            match *n < 0 {
+                true => E::Sub(b, Box::new(E::Nat(n.unsigned_abs()))),
+                false => E::Add(b, Box::new(E::Nat(*n as u32))),
+            }
+        }
+        Bound::Reversed(b) => E::Reverse(Box::new(bound_expr(b, of, input, var)?)),
+    })
+}
+
+/// What the type variables of a call stand for, as found by unifying them with the arguments
+#[derive(Debug, Default)]
+struct Substitution(HashMap<TypeVar, TypeLevelVal>);
+
+impl TypeEnv {
+    pub(crate) fn category_of(&self, name: &str) -> Option<Category> {
+        self.names.get(&Ident(name.to_string())).map(TType::category)
+    }
+    /// Registers a declaration, giving each of its variants' constructors a type
+    pub(crate) fn declare(&mut self, syntax: &DataSyntax) -> Result<Rc<DataDecl>, TypeCheckErrKind> {
+        let id = DataId(self.data.len() as u32);
+        // Types may refer to themselves
+        let scalar = |name: &str| match name == syntax.name {
+            true => Ok(TAtomKind::SumType(id)),
+            false => self.scalar_named(name),
+        };
+        let field = |ty: &FieldType| match ty {
+            FieldType::Named(name) => scalar(name).map(Known::scalar),
+            FieldType::Array { rank, scalar: name } => Ok(Known {
+                scalar: Some(scalar(name)?),
+                rank: Some(*rank),
+                ..Default::default()
+            }
+            .normalized()),
+        };
+        let variants = syntax
+            .variants
+            .iter()
+            .map(|v| {
+                let fields = v.fields.iter().map(|(name, ty)| Ok((name.clone(), field(ty)?)));
+                Ok(Variant {
+                    name: v.name.clone(),
+                    fields: fields.collect::<Result<_, _>>()?,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        let decl = Rc::new(DataDecl {
+            name: syntax.name.clone(),
+            id,
+            variants,
+        });
+        let value = Known::scalar(TAtomKind::SumType(id));
+        for (i, v) in decl.variants.iter().enumerate() {
+            let input = match &v.fields[..] {
+                [] => None,
+                [(_, k)] => Some(InputTypeSpecifier::of(k)),
+                fields => Some(InputTypeSpecifier::of(&Known::new(None, Some(vec![fields.len() as u32])))),
+            };
+            let ty = TType {
+                omega: input,
+                ..TType::noun(&value)
+            };
+            let ident = Ident(decl.constructor(i).to_string());
+            self.dfns.remove(&ident);
+            self.names.insert(ident, ty);
+        }
+        self.data.push(decl.clone());
+        Ok(decl)
+    }
+    /// Registers the type of a signature, for its name to take once it's bound. Type variables that
+    /// the output shares with the arguments are written in terms of the latter, so that the output
+    /// can be fed to other verbs
+    pub(crate) fn sign(&mut self, syntax: &SignatureSyntax) -> Result<(), TypeCheckErrKind> {
+        use TypeLevelExpr as E;
+        let mut classes = HashMap::<&str, Vec<Class>>::new();
+        for (class, v) in &syntax.constraints {
+            if !syntax.vars.contains(v) {
+                return Err(TypeCheckErrKind::UnknownType(v.clone()));
+            }
+            classes.entry(v).or_default().push(Class::named(class)?);
+        }
+        let var = |name: &str| match syntax.vars.iter().any(|v| v == name) {
+            true => Ok(E::Var(TypeVar {
+                name: name.to_string(),
+                instance: 0,
+                classes: classes.get(name).cloned().unwrap_or_default(),
+            })),
+            false => Err(TypeCheckErrKind::UnknownType(name.to_string())),
+        };
+        // Only the output may be relative to the arguments, and it must be clear which one
+        let sides = [(Side::Alpha, &syntax.alpha), (Side::Omega, &syntax.omega)]
+            .into_iter()
+            .filter_map(|(side, ty)| ty.as_ref().map(|_| side))
+            .collect::<Vec<_>>();
+        let argument = |side: Option<Side>| match (side, &sides[..]) {
+            (Some(side), _) if sides.contains(&side) => Ok(side),
+            (None, [side]) => Ok(*side),
+            _ => Err(TypeCheckErrKind::BadRelative),
+        };
+        let neither = |_| Err(TypeCheckErrKind::BadRelative);
+        let spec = |ty: &TypeSyntax, input: &dyn Fn(Option<Side>) -> Result<Side, TypeCheckErrKind>| {
+            let bound = |b: &Option<Bound>, of: fn(Side) -> TypeLevelExpr| {
+                b.as_ref().map(|b| bound_expr(b, of, input, &var)).transpose()
+            };
+            let mut spec = InputTypeSpecifier {
+                scalar: Some(match syntax.vars.contains(&ty.scalar) {
+                    true => var(&ty.scalar)?,
+                    false => E::Scalar(self.scalar_named(&ty.scalar)?),
+                }),
+                rank: bound(&ty.rank, E::RankOf)?,
+                length: bound(&ty.length, E::LengthOf)?,
+                ..Default::default()
+            };
+            match &ty.shape {
+                Some(Bound::Axes(axes)) => {
+                    spec.rank = spec.rank.or(Some(E::Nat(axes.len() as u32)));
+                    spec.length = spec.length.or(axes.first().and_then(|(_, n)| n.map(E::Nat)));
+                    spec.shape = axes.iter().map(|(_, n)| *n).collect::<Option<_>>().map(E::Shape);
+                    spec.axes = Some(E::Axes(axes.iter().map(|(name, _)| name.clone()).collect()));
+                }
+                shape => spec.shape = bound(shape, E::ShapeOf)?,
+            }
+            Ok(spec)
+        };
+        let alpha = syntax.alpha.as_ref().map(|ty| spec(ty, &neither)).transpose()?;
+        let omega = syntax.omega.as_ref().map(|ty| spec(ty, &neither)).transpose()?;
+        let out = spec(&syntax.output, &argument)?;
+        let mut anchor = |v: &TypeVar| {
+            let is = |e: &Option<TypeLevelExpr>| matches!(e, Some(E::Var(w)) if w == v);
+            for (spec, side) in [(&alpha, Side::Alpha), (&omega, Side::Omega)] {
+                match spec {
+                    Some(spec) if is(&spec.scalar) => return E::ScalarOf(side),
+                    Some(spec) if is(&spec.rank) => return E::RankOf(side),
+                    Some(spec) if is(&spec.length) => return E::LengthOf(side),
+                    Some(spec) if is(&spec.shape) => return E::ShapeOf(side),
+                    _ => {}
+                }
+            }
+            E::Var(v.clone())
+        };
+        let mut anchored = |e: Option<TypeLevelExpr>| e.map(|e| e.map_vars(&mut anchor).simplified());
+        let output = OutputTypeSpecifier {
+            scalar: anchored(out.scalar),
+            rank: anchored(out.rank),
+            length: anchored(out.length),
+            shape: anchored(out.shape),
+            axes: anchored(out.axes),
+        };
+        let ty = TType {
+            alpha,
+            omega,
+            output: Some(output),
+        };
+        self.signatures.insert(Ident(syntax.name.clone()), ty);
+        Ok(())
+    }
+    /// Binds `name` after its binding failed, to the type of its signature if it has one and to
+    /// anything of its category otherwise. Gives back whether it could be bound, which it can't
+    /// be if neither is known
+    pub(crate) fn placeholder(&mut self, name: &str, cat: Option<Category>) -> bool {
+        let ident = Ident(name.to_string());
+        self.dfns.remove(&ident);
+        let t = match (self.signatures.get(&ident), cat) {
+            (Some(signature), _) => signature.clone(),
+            (None, Some(cat)) => TType::verb(cat),
+            (None, None) => {
+                self.names.remove(&ident);
+                return false;
+            }
+        };
+        self.names.insert(ident, t);
+        true
+    }
+    /// `src` as kept by [`BoundDfn`]s, shared with those written in it already
+    fn source(&self, src: &str) -> Rc<str> {
+        match self.dfns.values().find(|d| &*d.src == src) {
+            Some(d) => d.src.clone(),
+            None => Rc::from(src),
+        }
+    }
+    /// Binds `name` to an array of which `k` is known, for names that are there from the start
+    pub(crate) fn predefine(&mut self, name: &str, k: &Known) {
+        self.names.insert(Ident(name.to_string()), TType::noun(k));
+    }
+    /// The names bound to something of a category `keep` holds for, sorted. System names aren't
+    /// included
+    pub(crate) fn bound(&self, keep: impl Fn(Category) -> bool) -> Vec<String> {
+        let mut names = (self.names.iter())
+            .filter(|(name, t)| !name.0.starts_with('⎕') && keep(t.category()))
+            .map(|(name, _)| name.0.clone())
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+    /// Unbinds `name` and forgets its signature, giving back whether it was bound
+    pub(crate) fn erase(&mut self, name: &Ident) -> bool {
+        self.signatures.remove(name);
+        self.dfns.remove(name);
+        self.names.remove(name).is_some()
+    }
+    /// Have every function bound from now on need a signature
+    pub(crate) fn require_signatures(&mut self) {
+        self.strict = true;
+    }
+    /// `name`'s type written as a signature, with what's unknown about it left generic. Dfns bound
+    /// without one get what their bodies say, if it can be written
+    pub(crate) fn signature_of(&self, name: &str) -> Option<String> {
+        let ident = Ident(name.to_string());
+        let t = self.names.get(&ident)?;
+        match self.dfns.get(&ident) {
+            Some(dfn) => inferred(&dfn.src, &dfn.body, dfn.depth, t, self).map(|t| self.written(Some(name), &t)),
+            None => Some(self.written(Some(name), t)),
+        }
+    }
+    /// `t` as it would be written in a signature, just the type for arrays and the whole signature
+    /// for functions, which are called `ƒ`
+    pub(crate) fn described(&self, t: &TType) -> String {
+        self.written(None, t)
+    }
+    /// `t` as the signature of `name`. The inputs' unknown scalar types become type variables, as
+    /// does whatever the output takes from them but isn't known
+    fn written(&self, name: Option<&str>, t: &TType) -> String {
+        use TypeLevelExpr as E;
+        let mut vars: Vec<(String, Vec<Class>)> = vec![];
+        t.map_vars(&mut |v| {
+            if !vars.iter().any(|(name, _)| *name == v.name) {
+                vars.push((v.name.clone(), v.classes.clone()));
+            }
+            E::Var(v.clone())
+        });
+        let fresh = |base: &str, vars: &mut Vec<(String, Vec<Class>)>| {
+            let mut name = base.to_string();
+            while vars.iter().any(|(v, _)| *v == name) {
+                name.push('′');
+            }
+            vars.push((name.clone(), vec![]));
+            name
+        };
+        // Scalar, rank, length then shape, as they'd be written in a type
+        const PREFIXES: [&str; 4] = ["", "⍤", "≢", "⍴"];
+        let written = |field: usize, e: &E| match (field, e) {
+            (_, E::Var(v)) => Some(format!("{}{}", PREFIXES[field], v.name)),
+            (0, E::Scalar(k)) => Some(self.kind_name(*k)),
+            (1, E::Nat(n)) => Some(n.to_string()),
+            (2, E::Nat(n)) => Some(format!("≢={n}")),
+            (3, E::Shape(sh)) if !sh.is_empty() => {
+                Some(format!("⍴={}", sh.iter().map(u32::to_string).collect::<Vec<_>>().join("_")))
+            }
+            _ => None,
+        };
+        // `⍤-1` and the like, subscripted when there are two arguments
+        let of = |field: usize, e: &E| match (field, e) {
+            (1, E::RankOf(side)) | (2, E::LengthOf(side)) | (3, E::ShapeOf(side)) => {
+                let both = t.alpha.is_some() && t.omega.is_some();
+                Some(format!("{}{}", PREFIXES[field], if both { side.pick("₁", "₂") } else { "" }))
+            }
+            _ => None,
+        };
+        let relative = |field: usize, e: &E| match e {
+            E::Add(x, y) | E::Sub(x, y) => match &**y {
+                E::Nat(n) => {
+                    let sign = if matches!(e, E::Add(..)) { '+' } else { '-' };
+                    of(field, x).map(|of| format!("{of}{sign}{n}"))
+                }
+                _ => None,
+            },
+            E::Reverse(x) => of(field, x).map(|of| format!("{of}⇌")),
+            _ => None,
+        };
+        let mut sides = [&t.alpha, &t.omega].map(|spec| {
+            spec.as_ref().map(|spec| {
+                let fields = [&spec.scalar, &spec.rank, &spec.length, &spec.shape];
+                let mut side = [0, 1, 2, 3].map(|field| fields[field].as_ref().and_then(|e| written(field, e)));
+                if let Some(E::Axes(names)) = &spec.axes {
+                    let sizes = match &spec.shape {
+                        Some(E::Shape(sh)) => sh.iter().map(|&n| Some(n)).collect(),
+                        _ => vec![None; names.len()],
+                    };
+                    let axes = names.iter().zip(sizes).map(|axis| match axis {
+                        (Some(name), Some(n)) => format!("{name}={n}"),
+                        (Some(name), None) => name.clone(),
+                        (None, Some(n)) => n.to_string(),
+                        (None, None) => "?".to_string(),
+                    });
+                    side[3] = Some(format!("⍴[{}]", axes.collect::<Vec<_>>().join(", ")));
+                }
+                side
+            })
+        });
+        for side in sides.iter_mut().flatten() {
+            if side[0].is_none() {
+                side[0] = Some(fresh("T", &mut vars));
+            }
+        }
+        let output = t.output.clone().unwrap_or_default();
+        let mut out: [Option<String>; 4] = Default::default();
+        for (field, e) in [&output.scalar, &output.rank, &output.length, &output.shape].into_iter().enumerate() {
+            let from = match e {
+                Some(E::ScalarOf(side)) if field == 0 => Some(*side),
+                Some(E::RankOf(side)) if field == 1 => Some(*side),
+                Some(E::LengthOf(side)) if field == 2 => Some(*side),
+                Some(E::ShapeOf(side)) if field == 3 => Some(*side),
+                _ => None,
+            };
+            out[field] = match from {
+                Some(side) => sides[side as usize].as_mut().map(|side| match &side[field] {
+                    Some(w) => w.clone(),
+                    None => {
+                        let w = format!("{}{}", PREFIXES[field], fresh(["T", "R", "N", "S"][field], &mut vars));
+                        side[field] = Some(w.clone());
+                        w
+                    }
+                }),
+                None => {
+                    // What the output is relative to must be written for it to refer to
+                    if let Some(E::Add(x, _) | E::Sub(x, _) | E::Reverse(x)) = e
+                        && let E::RankOf(side) | E::LengthOf(side) | E::ShapeOf(side) = **x
+                        && let Some(side) = sides[side as usize].as_mut()
+                    {
+                        side[field].get_or_insert_with(|| PREFIXES[field].to_string());
+                    }
+                    e.as_ref().and_then(|e| written(field, e).or_else(|| relative(field, e)))
+                }
+            };
+        }
+        if out[0].is_none() {
+            out[0] = Some(fresh("T", &mut vars));
+        }
+        let ty = |[scalar, rest @ ..]: &[Option<String>; 4]| {
+            let scalar = scalar.as_deref().expect("scalar types are always written");
+            match rest {
+                [Some(rank), ..] if rank == "0" => format!("'{scalar}"),
+                _ => {
+                    let rest = rest.iter().flatten().map(String::as_str);
+                    format!("'({})", rest.chain([scalar]).collect::<Vec<_>>().join(", "))
+                }
+            }
+        };
+        if name.is_none() && sides.iter().all(Option::is_none) {
+            return ty(&out);
+        }
+        let name = name.unwrap_or("ƒ");
+        let mut text = ":".to_string();
+        if !vars.is_empty() {
+            text.push_str(" ∀");
+            text.push_str(&vars.iter().map(|(v, _)| v.as_str()).collect::<Vec<_>>().join(" "));
+            for (v, classes) in &vars {
+                for class in classes {
+                    text.push_str(&format!(" ({class:?} {v})"));
+                }
+            }
+        }
+        if let Some(alpha) = &sides[0] {
+            text.push_str(&format!(" {} »", ty(alpha)));
+        }
+        text.push_str(&format!(" {name}"));
+        if let Some(omega) = &sides[1] {
+            text.push_str(&format!(" « {}", ty(omega)));
+        }
+        text.push_str(&format!(" ↳ {}", ty(&out)));
+        text
+    }
+    /// Every scalar type, the declared ones included
+    fn scalar_kinds(&self) -> Vec<TAtomKind> {
+        use TAtomKind::*;
+        [Nat, Int, U8, U16, I8, I16, Char]
+            .into_iter()
+            .chain((0..self.data.len() as u32).map(|i| SumType(DataId(i))))
+            .chain([AVerb, OVerb, DVerb, AAdverb, OAdverb, DDyadic])
+            .collect()
+    }
+    /// How a scalar type is written in a signature
+    fn kind_name(&self, k: TAtomKind) -> String {
+        match k {
+            TAtomKind::SumType(id) => self.data[id.0 as usize].name.clone(),
+            k => format!("{k:?}"),
+        }
+    }
+    /// A built-in scalar type, or a declared one
+    fn scalar_named(&self, name: &str) -> Result<TAtomKind, TypeCheckErrKind> {
+        match name.to_lowercase().as_str() {
+            "nat" => Ok(TAtomKind::Nat),
+            "int" => Ok(TAtomKind::Int),
+            "u8" => Ok(TAtomKind::U8),
+            "u16" => Ok(TAtomKind::U16),
+            "i8" => Ok(TAtomKind::I8),
+            "i16" => Ok(TAtomKind::I16),
+            "char" => Ok(TAtomKind::Char),
+            _ => match self.data.iter().rev().find(|d| d.name == name) {
+                Some(d) => Ok(TAtomKind::SumType(d.id)),
+                None => Err(TypeCheckErrKind::UnknownType(name.to_string())),
+            },
+        }
+    }
+    /// `t` with fresh type variables, so that what one use of it binds them to doesn't constrain
+    /// others. Variables that were apart stay apart
+    fn instantiate(&mut self, t: &TType) -> TType {
+        let mut fresh = HashMap::new();
+        let instances = &mut self.instances;
+        t.map_vars(&mut |v| {
+            let instance = *fresh.entry(v.instance).or_insert_with(|| {
+                *instances += 1;
+                *instances
+            });
+            TypeLevelExpr::Var(TypeVar {
+                instance,
+                ..v.clone()
+            })
+        })
+    }
+    /// The declaration of the type of values described by `k`, if it's user-defined
+    fn decl_of(&self, k: &Known) -> Option<Rc<DataDecl>> {
+        match (k.scalar, k.rank) {
+            (Some(TAtomKind::SumType(id)), Some(0)) => Some(self.data[id.0 as usize].clone()),
+            _ => None,
+        }
+    }
+    /// The declaration and variant `name` builds, if it's a constructor
+    fn constructor(&self, name: &str) -> Option<(&DataDecl, usize)> {
+        self.data
+            .iter()
+            .rev()
+            .find_map(|d| d.variant(name).map(|v| (&**d, v)))
+    }
+}
+
+impl Known {
+    pub(crate) fn new(scalar: Option<TAtomKind>, shape: Option<Shape>) -> Self {
+        Self {
+            scalar,
+            shape,
+            ..Default::default()
+        }
+        .normalized()
+    }
+    fn scalar(kind: TAtomKind) -> Self {
+        Self::new(Some(kind), Some(vec![]))
+    }
+    /// Fills in rank and length from the shape or axes, if they're known
+    fn normalized(mut self) -> Self {
+        if self.rank == Some(0) {
+            self.shape = Some(vec![]);
+        }
+        if self.axes.as_ref().is_some_and(|axes| axes.iter().all(Option::is_none)) {
+            self.axes = None;
+        }
+        if let Some(axes) = &self.axes {
+            self.rank = Some(axes.len() as u32);
+        }
+        if let Some(sh) = &self.shape {
+            self.rank = Some(sh.len() as u32);
+            self.length = Some(sh.first().copied().unwrap_or(1));
+        }
+        self
+    }
+    /// What is known about both `self` and `other`
+    fn common(&self, other: &Self) -> Self {
+        fn same<T: PartialEq + Clone>(a: &Option<T>, b: &Option<T>) -> Option<T> {
+            a.clone().filter(|_| a == b)
+        }
+        Self {
+            scalar: same(&self.scalar, &other.scalar),
+            rank: same(&self.rank, &other.rank),
+            length: same(&self.length, &other.length),
+            shape: same(&self.shape, &other.shape),
+            axes: same(&self.axes, &other.axes),
+        }
+    }
+    /// What is known about an array with `n` major cells like this one
+    fn framed(&self, n: u32) -> Self {
+        Self {
+            scalar: self.scalar,
+            rank: self.rank.map(|r| r + 1),
+            length: Some(n),
+            shape: self.shape.as_ref().map(|sh| [vec![n], sh.clone()].concat()),
+            axes: self.axes.as_ref().map(|axes| [vec![None], axes.clone()].concat()),
+        }
+    }
+    /// What is known about each of the major cells
+    fn major_cell(&self) -> Self {
+        match self.rank {
+            Some(0) => self.clone(),
+            rank => Self {
+                scalar: self.scalar,
+                rank: rank.map(|r| r - 1),
+                length: None,
+                shape: self.shape.as_ref().map(|sh| sh[1..].to_vec()),
+                axes: self.axes.as_ref().map(|axes| axes[1..].to_vec()),
+            }
+            .normalized(),
+        }
+    }
+    /// With the order of the axes reversed
+    fn transposed(&self) -> Self {
+        Self {
+            scalar: self.scalar,
+            rank: self.rank,
+            length: self.length.filter(|_| self.rank.is_some_and(|r| r <= 1)),
+            shape: self.shape.as_ref().map(|sh| sh.iter().rev().copied().collect()),
+            axes: self.axes.as_ref().map(|axes| axes.iter().rev().cloned().collect()),
+        }
+        .normalized()
+    }
+    /// Whether these could describe the same array
+    fn compatible(&self, other: &Known) -> bool {
+        fn agree<T: PartialEq>(a: &Option<T>, b: &Option<T>) -> bool {
+            a.is_none() || b.is_none() || a == b
+        }
+        agree(&self.scalar, &other.scalar)
+            && agree(&self.rank, &other.rank)
+            && agree(&self.length, &other.length)
+            && agree(&self.shape, &other.shape)
+            && agree(&self.axes, &other.axes)
+    }
+    /// The size of each axis, as far as it's known
+    fn sizes(&self) -> Option<Vec<Option<u32>>> {
+        match (&self.shape, self.rank) {
+            (Some(sh), _) => Some(sh.iter().copied().map(Some).collect()),
+            (None, Some(r)) => Some((0..r).map(|i| self.length.filter(|_| i == 0)).collect()),
+            (None, None) => None,
+        }
+    }
+}
+
+impl Display for TypeCheckErrKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use TypeCheckErrKind as K;
+        match self {
+            K::UnknownName => write!(f, "unknown name"),
+            K::ScalarMismatch { expected, found } => write!(f, "expected {expected:?}, found {found:?}"),
+            K::NotNumeric(k) => write!(f, "{k:?} is not a number"),
+            K::RankMismatch { expected, found } => write!(f, "expected rank {expected}, found {found}"),
+            K::LengthMismatch { expected, found } => write!(f, "expected length {expected}, found {found}"),
+            K::ShapeMismatch { expected, found } => write!(f, "expected shape {expected:?}, found {found:?}"),
+            K::NotAVerb => write!(f, "expected a verb"),
+            K::NotANoun => write!(f, "expected an array"),
+            K::RepeatCount => write!(f, "⍣ needs a scalar Nat count"),
+            K::RepeatNotClosed { input, output } => write!(f, "⍣ is given {input} but gives back {output}"),
+            K::NotMonadic => write!(f, "expected a monadic verb"),
+            K::NotDyadic => write!(f, "expected a dyadic verb"),
+            K::NoInverse => write!(f, "⍜ can't undo this function"),
+            K::UnknownType(name) => write!(f, "unknown type {name}"),
+            K::NoField(name) => write!(f, "no field {name}"),
+            K::UnknownVariant(name) => write!(f, "no variant {name}"),
+            K::NonExhaustive(missing) => write!(f, "⊔ doesn't cover {}", missing.join(", ")),
+            K::AxisMismatch { expected, found } => write!(f, "expected axes {expected}, found {found}"),
+            K::Conflict { var, first, second } => write!(f, "{var} is both {first} and {second}"),
+            K::BadRelative => write!(f, "⍤-1 and the like need one argument to be relative to, or ₁ for ⍺ and ₂ for ω"),
+            K::UnknownClass(name) => write!(f, "unknown class {name}"),
+            K::NoInstance { class, found } => write!(f, "{found} is not {class:?}"),
+            K::Unsatisfied { declared, found } => write!(f, "signature says {declared}, body gives {found}"),
+            K::NoSignature { name, inferred: Some(inferred) } => {
+                write!(f, "no signature for {name}, which could be `{inferred}`")
+            }
+            K::NoSignature { name, inferred: None } => write!(f, "no signature for {name}"),
+            K::Unbound(name) => write!(f, "signature for {name} isn't followed by its binding"),
+        }
+    }
+}
+
+impl TypeCheckErrKind {
+    /// Identifies the kind of error for tooling. Never reused or renumbered
+    pub fn code(&self) -> &'static str {
+        use TypeCheckErrKind as K;
+        match self {
+            K::UnknownName => "T001",
+            K::ScalarMismatch { .. } => "T002",
+            K::NotNumeric(_) => "T003",
+            K::RankMismatch { .. } => "T004",
+            K::LengthMismatch { .. } => "T005",
+            K::ShapeMismatch { .. } => "T006",
+            K::NotAVerb => "T007",
+            K::NotANoun => "T008",
+            K::RepeatCount => "T009",
+            K::RepeatNotClosed { .. } => "T010",
+            K::NotMonadic => "T011",
+            K::NotDyadic => "T012",
+            K::NoInverse => "T013",
+            K::UnknownType(_) => "T014",
+            K::NoField(_) => "T015",
+            K::UnknownVariant(_) => "T016",
+            K::NonExhaustive(_) => "T017",
+            K::AxisMismatch { .. } => "T018",
+            K::Conflict { .. } => "T019",
+            K::BadRelative => "T020",
+            K::UnknownClass(_) => "T021",
+            K::NoInstance { .. } => "T022",
+            K::Unsatisfied { .. } => "T023",
+            K::NoSignature { .. } => "T024",
+            K::Unbound(_) => "T025",
+        }
+    }
+}
+
+impl Display for TypeLevelVal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TypeLevelVal::Nat(n) => write!(f, "{n}"),
+            TypeLevelVal::Shape(sh) => write!(f, "{sh:?}"),
+            TypeLevelVal::Scalar(k) => write!(f, "{k:?}"),
+            TypeLevelVal::Axes(axes) => {
+                let names = axes.iter().map(|a| a.as_deref().unwrap_or("?"));
+                write!(f, "[{}]", names.collect::<Vec<_>>().join(", "))
+            }
+        }
+    }
+}
+
+/// Axes are written with their names and sizes, e.g. `[rows=3, cols=?] Nat`
+impl Display for Known {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.sizes() {
+            Some(sizes) => {
+                let axes = sizes.iter().enumerate().map(|(i, n)| {
+                    let n = n.map_or("?".to_string(), |n| n.to_string());
+                    match self.axes.as_ref().and_then(|axes| axes[i].as_ref()) {
+                        Some(name) => format!("{name}={n}"),
+                        None => n,
+                    }
+                });
+                write!(f, "[{}]", axes.collect::<Vec<_>>().join(", "))?;
+            }
+            None => write!(f, "[…]")?,
+        }
+        match self.scalar {
+            Some(k) => write!(f, " {k:?}"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl TypeLevelExpr {
+    fn eval(&self, alpha: Option<&Known>, omega: Option<&Known>) -> Result<Option<TypeLevelVal>, TypeCheckErrKind> {
+        use TypeLevelVal as V;
+        let side = |s: &Side| match s {
+            Side::Alpha => alpha,
+            Side::Omega => omega,
+        };
+        Ok(match self {
+            TypeLevelExpr::Nat(n) => Some(V::Nat(*n)),
+            TypeLevelExpr::Shape(sh) => Some(V::Shape(sh.clone())),
+            TypeLevelExpr::Scalar(k) => Some(V::Scalar(*k)),
+            TypeLevelExpr::Axes(axes) => Some(V::Axes(axes.clone())),
+            // Only known once it's been unified with something, see `Substitution::apply`
+            TypeLevelExpr::Var(_) => None,
+            TypeLevelExpr::RankOf(s) => side(s).and_then(|k| k.rank).map(V::Nat),
+            TypeLevelExpr::LengthOf(s) => side(s).and_then(|k| k.length).map(V::Nat),
+            TypeLevelExpr::ShapeOf(s) => side(s).and_then(|k| k.shape.clone()).map(V::Shape),
+            TypeLevelExpr::ScalarOf(s) => side(s).and_then(|k| k.scalar).map(V::Scalar),
+            TypeLevelExpr::AxesOf(s) => side(s).and_then(|k| k.axes.clone()).map(V::Axes),
+            TypeLevelExpr::Add(a, b) => match (a.eval(alpha, omega)?, b.eval(alpha, omega)?) {
+                (Some(V::Nat(a)), Some(V::Nat(b))) => Some(V::Nat(a + b)),
+                _ => None,
+            },
+            TypeLevelExpr::Sub(a, b) => match (a.eval(alpha, omega)?, b.eval(alpha, omega)?) {
+                (Some(V::Nat(a)), Some(V::Nat(b))) => Some(V::Nat(a.saturating_sub(b))),
+                _ => None,
+            },
+            TypeLevelExpr::Reverse(a) => match a.eval(alpha, omega)? {
+                Some(V::Shape(mut sh)) => {
+                    sh.reverse();
+                    Some(V::Shape(sh))
+                }
+                Some(V::Axes(mut axes)) => {
+                    axes.reverse();
+                    Some(V::Axes(axes))
+                }
+                _ => None,
+            },
+            TypeLevelExpr::Pervade(a, b) => match (a.eval(alpha, omega)?, b.eval(alpha, omega)?) {
+                (Some(V::Nat(0)), other) | (other, Some(V::Nat(0))) => other,
+                (Some(V::Nat(a)), Some(V::Nat(b))) if a != b => {
+                    return Err(TypeCheckErrKind::RankMismatch { expected: a, found: b });
+                }
+                (Some(V::Shape(a)), other) | (other, Some(V::Shape(a))) if a.is_empty() => other,
+                (Some(V::Shape(a)), Some(V::Shape(b))) if a.len() != b.len() => {
+                    return Err(TypeCheckErrKind::RankMismatch {
+                        expected: a.len() as u32,
+                        found: b.len() as u32,
+                    });
+                }
+                (Some(V::Shape(a)), Some(V::Shape(b))) if a != b => {
+                    return Err(TypeCheckErrKind::ShapeMismatch { expected: a, found: b });
+                }
+                // Names must agree even where sizes do, and unnamed axes take the other's names
+                (Some(V::Axes(a)), Some(V::Axes(b))) if a.len() == b.len() => match merged_axes(&a, &b) {
+                    Some(axes) => Some(V::Axes(axes)),
+                    None => {
+                        let named = |k: Option<&Known>, axes| {
+                            Known {
+                                axes: Some(axes),
+                                ..k.cloned().unwrap_or_default()
+                            }
+                            .normalized()
+                        };
+                        return Err(TypeCheckErrKind::AxisMismatch {
+                            expected: Box::new(named(alpha, a)),
+                            found: Box::new(named(omega, b)),
+                        });
+                    }
+                },
+                (Some(V::Axes(a)), None) | (None, Some(V::Axes(a))) => Some(V::Axes(a)),
+                (Some(a), Some(_)) => Some(a),
+                _ => None,
+            },
+            TypeLevelExpr::Promote(a, b) => match (a.eval(alpha, omega)?, b.eval(alpha, omega)?) {
+                (Some(V::Scalar(a)), Some(V::Scalar(b))) => match primitive::arithmetic::promote(a, b) {
+                    Some(k) => Some(V::Scalar(k)),
+                    None if primitive::arithmetic::promote(a, a).is_none() => {
+                        return Err(TypeCheckErrKind::NotNumeric(a));
+                    }
+                    None => return Err(TypeCheckErrKind::NotNumeric(b)),
+                },
+                _ => None,
+            },
+            TypeLevelExpr::Signed(a) => match a.eval(alpha, omega)? {
+                Some(V::Scalar(k)) => match primitive::arithmetic::signed(k) {
+                    Some(k) => Some(V::Scalar(k)),
+                    None => return Err(TypeCheckErrKind::NotNumeric(k)),
+                },
+                _ => None,
+            },
+        })
+    }
+}
+
+/// The names of axes that both `a` and `b` describe, `None` if they disagree on one
+fn merged_axes(a: &Axes, b: &Axes) -> Option<Axes> {
+    if a.len() != b.len() {
+        return None;
+    }
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| match (a, b) {
+            (Some(a), Some(b)) if a != b => None,
+            (a, b) => Some(a.clone().or(b.clone())),
+        })
+        .collect()
+}
+
+impl Class {
+    fn named(name: &str) -> Result<Self, TypeCheckErrKind> {
+        match name {
+            "Eq" => Ok(Class::Eq),
+            "Ord" => Ok(Class::Ord),
+            "Num" => Ok(Class::Num),
+            _ => Err(TypeCheckErrKind::UnknownClass(name.to_string())),
+        }
+    }
+    /// Whether `k` is an instance of the class
+    fn has(self, k: TAtomKind) -> bool {
+        use TAtomKind::*;
+        match self {
+            Class::Eq => !matches!(k, AVerb | OVerb | DVerb | AAdverb | OAdverb | DDyadic),
+            Class::Ord => k == Char || Class::Num.has(k),
+            Class::Num => primitive::arithmetic::promote(k, k).is_some(),
+        }
+    }
+}
+
+impl Substitution {
+    /// Has `var` stand for `val`, unless it already stands for something else
+    fn bind(&mut self, var: &TypeVar, val: TypeLevelVal) -> Result<(), TypeCheckErrKind> {
+        if let Some(&class) = var.classes.iter().find(|c| !matches!(val, TypeLevelVal::Scalar(k) if c.has(k))) {
+            return Err(TypeCheckErrKind::NoInstance { class, found: val });
+        }
+        match self.0.get(var) {
+            Some(first) if *first != val => Err(TypeCheckErrKind::Conflict {
+                var: var.name.clone(),
+                first: Box::new(first.clone()),
+                second: Box::new(val),
             }),
-        }),
-        ExprTokenKind::String => Ok(TType {
+            Some(_) => Ok(()),
+            None => {
+                self.0.insert(var.clone(), val);
+                Ok(())
+            }
+        }
+    }
+    /// `e` with the variables bound so far replaced by what they stand for
+    fn apply(&self, e: &TypeLevelExpr) -> TypeLevelExpr {
+        e.map_vars(&mut |v| match self.0.get(v) {
+            Some(TypeLevelVal::Nat(n)) => TypeLevelExpr::Nat(*n),
+            Some(TypeLevelVal::Shape(sh)) => TypeLevelExpr::Shape(sh.clone()),
+            Some(TypeLevelVal::Scalar(k)) => TypeLevelExpr::Scalar(*k),
+            Some(TypeLevelVal::Axes(axes)) => TypeLevelExpr::Axes(axes.clone()),
+            None => TypeLevelExpr::Var(v.clone()),
+        })
+    }
+}
+
+impl InputTypeSpecifier {
+    fn check(&self, k: &Known) -> Result<(), TypeCheckErrKind> {
+        self.unify(k, &mut Substitution::default())
+    }
+    /// Checks that `k` is what's asked for, binding the type variables along the way
+    fn unify(&self, k: &Known, vars: &mut Substitution) -> Result<(), TypeCheckErrKind> {
+        use TypeLevelVal as V;
+        // What a part asks for, if it's not a variable
+        let mut expect = |spec: &Option<TypeLevelExpr>, found: Option<V>| match (spec, found) {
+            (Some(TypeLevelExpr::Var(v)), Some(found)) => vars.bind(v, found).map(|_| None),
+            (Some(spec), Some(found)) => Ok(spec.eval(None, None)?.map(|expected| (expected, found))),
+            _ => Ok(None),
+        };
+        if let Some((V::Scalar(expected), V::Scalar(found))) = expect(&self.scalar, k.scalar.map(V::Scalar))?
+            && expected != found
+        {
+            return Err(TypeCheckErrKind::ScalarMismatch { expected, found });
+        }
+        if let Some((V::Nat(expected), V::Nat(found))) = expect(&self.rank, k.rank.map(V::Nat))?
+            && expected != found
+        {
+            return Err(TypeCheckErrKind::RankMismatch { expected, found });
+        }
+        if let Some((V::Nat(expected), V::Nat(found))) = expect(&self.length, k.length.map(V::Nat))?
+            && expected != found
+        {
+            return Err(TypeCheckErrKind::LengthMismatch { expected, found });
+        }
+        if let Some((V::Shape(expected), V::Shape(found))) = expect(&self.shape, k.shape.clone().map(V::Shape))?
+            && expected != found
+        {
+            return Err(TypeCheckErrKind::ShapeMismatch { expected, found });
+        }
+        if let Some((V::Axes(expected), V::Axes(found))) = expect(&self.axes, k.axes.clone().map(V::Axes))?
+            && merged_axes(&expected, &found).is_none()
+        {
+            return Err(TypeCheckErrKind::AxisMismatch {
+                expected: Box::new(self.refine(&Known::default(), vars)),
+                found: Box::new(k.clone()),
+            });
+        }
+        Ok(())
+    }
+    /// `k`, along with whatever else it must be to have been accepted
+    fn refine(&self, k: &Known, vars: &Substitution) -> Known {
+        let spec = |e: &Option<TypeLevelExpr>| e.as_ref().and_then(|e| vars.apply(e).eval(None, None).ok().flatten());
+        let nat = |v| match v {
+            Some(TypeLevelVal::Nat(n)) => Some(n),
+            _ => None,
+        };
+        Known {
+            scalar: k.scalar.or(match spec(&self.scalar) {
+                Some(TypeLevelVal::Scalar(s)) => Some(s),
+                _ => None,
+            }),
+            rank: k.rank.or(nat(spec(&self.rank))),
+            length: k.length.or(nat(spec(&self.length))),
+            shape: k.shape.clone().or(match spec(&self.shape) {
+                Some(TypeLevelVal::Shape(sh)) => Some(sh),
+                _ => None,
+            }),
+            axes: match (&k.axes, spec(&self.axes)) {
+                (Some(a), Some(TypeLevelVal::Axes(b))) => merged_axes(a, &b).or(Some(a.clone())),
+                (None, Some(TypeLevelVal::Axes(b))) => Some(b),
+                (a, _) => a.clone(),
+            },
+        }
+        .normalized()
+    }
+    /// Replaces every type variable with what `f` gives for it
+    fn map_vars(&self, f: &mut dyn FnMut(&TypeVar) -> TypeLevelExpr) -> Self {
+        let mut map = |e: &Option<TypeLevelExpr>| e.as_ref().map(|e| e.map_vars(f));
+        Self {
+            scalar: map(&self.scalar),
+            rank: map(&self.rank),
+            length: map(&self.length),
+            shape: map(&self.shape),
+            axes: map(&self.axes),
+        }
+    }
+}
+
+impl InputTypeSpecifier {
+    /// Takes exactly what's described by `k`
+    fn of(k: &Known) -> Self {
+        Self {
+            scalar: k.scalar.map(TypeLevelExpr::Scalar),
+            rank: k.rank.map(TypeLevelExpr::Nat),
+            length: k.length.map(TypeLevelExpr::Nat),
+            shape: k.shape.clone().map(TypeLevelExpr::Shape),
+            axes: k.axes.clone().map(TypeLevelExpr::Axes),
+        }
+    }
+    /// What both `a` and `b` ask for. `a` wins where they disagree
+    fn merge(a: &Option<Self>, b: &Option<Self>) -> Option<Self> {
+        match (a, b) {
+            (Some(a), Some(b)) => Some(Self {
+                scalar: a.scalar.clone().or(b.scalar.clone()),
+                rank: a.rank.clone().or(b.rank.clone()),
+                length: a.length.clone().or(b.length.clone()),
+                shape: a.shape.clone().or(b.shape.clone()),
+                axes: a.axes.clone().or(b.axes.clone()),
+            }),
+            (a, b) => a.clone().or(b.clone()),
+        }
+    }
+}
+
+impl OutputTypeSpecifier {
+    /// The output of feeding what `alpha` and `omega` describe to a function with this output
+    fn compose(&self, alpha: Option<&OutputTypeSpecifier>, omega: Option<&OutputTypeSpecifier>) -> Self {
+        let sub = |e: &Option<TypeLevelExpr>| e.as_ref().and_then(|e| e.substitute(alpha, omega)).map(TypeLevelExpr::simplified);
+        Self {
+            scalar: sub(&self.scalar),
+            rank: sub(&self.rank),
+            length: sub(&self.length),
+            shape: sub(&self.shape),
+            axes: sub(&self.axes),
+        }
+    }
+    /// Everything about the output of a function with these inputs
+    fn same_as(side: Side) -> Self {
+        Self {
+            scalar: Some(TypeLevelExpr::ScalarOf(side)),
+            rank: Some(TypeLevelExpr::RankOf(side)),
+            length: Some(TypeLevelExpr::LengthOf(side)),
+            shape: Some(TypeLevelExpr::ShapeOf(side)),
+            axes: Some(TypeLevelExpr::AxesOf(side)),
+        }
+    }
+    /// Replaces every type variable with what `f` gives for it
+    fn map_vars(&self, f: &mut dyn FnMut(&TypeVar) -> TypeLevelExpr) -> Self {
+        let mut map = |e: &Option<TypeLevelExpr>| e.as_ref().map(|e| e.map_vars(f));
+        Self {
+            scalar: map(&self.scalar),
+            rank: map(&self.rank),
+            length: map(&self.length),
+            shape: map(&self.shape),
+            axes: map(&self.axes),
+        }
+    }
+    fn eval(&self, alpha: Option<&Known>, omega: Option<&Known>) -> Result<Known, TypeCheckErrKind> {
+        let eval = |e: &Option<TypeLevelExpr>| match e {
+            Some(e) => e.eval(alpha, omega),
+            None => Ok(None),
+        };
+        let nat = |v| match v {
+            Some(TypeLevelVal::Nat(n)) => Some(n),
+            _ => None,
+        };
+        let known = Known {
+            scalar: match eval(&self.scalar)? {
+                Some(TypeLevelVal::Scalar(k)) => Some(k),
+                _ => None,
+            },
+            rank: nat(eval(&self.rank)?),
+            length: nat(eval(&self.length)?),
+            shape: match eval(&self.shape)? {
+                Some(TypeLevelVal::Shape(sh)) => Some(sh),
+                _ => None,
+            },
+            axes: match eval(&self.axes)? {
+                Some(TypeLevelVal::Axes(axes)) => Some(axes),
+                _ => None,
+            },
+        };
+        Ok(known.normalized())
+    }
+}
+
+impl TType {
+    pub(crate) fn noun(k: &Known) -> Self {
+        Self {
             alpha: None,
             omega: None,
-            output: Some(OTS {
-                rank: Some(Box::new(|_, _| 1)),
-                length: Some(Box::new(|_, _| todo!())),
-                shape: Some(Box::new(|_, _| todo!())),
+            output: Some(OutputTypeSpecifier {
+                scalar: k.scalar.map(TypeLevelExpr::Scalar),
+                rank: k.rank.map(TypeLevelExpr::Nat),
+                length: k.length.map(TypeLevelExpr::Nat),
+                shape: k.shape.clone().map(TypeLevelExpr::Shape),
+                axes: k.axes.clone().map(TypeLevelExpr::Axes),
             }),
+        }
+    }
+    /// A verb of this category that takes anything and about whose output nothing is known
+    pub(crate) fn verb(cat: Category) -> Self {
+        let any = || Some(InputTypeSpecifier::default());
+        Self {
+            alpha: any().filter(|_| matches!(cat, Category::Av | Category::Dv)),
+            omega: any().filter(|_| matches!(cat, Category::Ov | Category::Dv)),
+            output: None,
+        }
+    }
+    /// For nouns, what is known about their value
+    pub(crate) fn known(&self) -> Known {
+        self.output
+            .as_ref()
+            .and_then(|o| o.eval(None, None).ok())
+            .unwrap_or_default()
+    }
+    pub(crate) fn category(&self) -> Category {
+        match (&self.alpha, &self.omega) {
+            (None, None) => Category::A,
+            (Some(_), None) => Category::Av,
+            (None, Some(_)) => Category::Ov,
+            (Some(_), Some(_)) => Category::Dv,
+        }
+    }
+    /// The type of calling a verb of this type with arguments of the given types. Type variables
+    /// must stand for the same thing everywhere they appear, and what they're found to stand for
+    /// fills in what's unknown about the arguments
+    fn apply(&self, alpha: Option<&Known>, omega: Option<&Known>) -> Result<Known, TypeCheckErrKind> {
+        let mut vars = Substitution::default();
+        for (spec, arg) in [(&self.alpha, alpha), (&self.omega, omega)] {
+            if let (Some(spec), Some(arg)) = (spec, arg) {
+                spec.unify(arg, &mut vars)?;
+            }
+        }
+        let refined = |spec: &Option<InputTypeSpecifier>, arg: Option<&Known>| match spec {
+            Some(spec) => arg.map(|arg| spec.refine(arg, &vars)),
+            None => arg.cloned(),
+        };
+        let (alpha, omega) = (refined(&self.alpha, alpha), refined(&self.omega, omega));
+        match &self.output {
+            Some(o) => o.eval(alpha.as_ref(), omega.as_ref()),
+            None => Ok(Known::default()),
+        }
+    }
+    /// Replaces every type variable with what `f` gives for it
+    fn map_vars(&self, f: &mut dyn FnMut(&TypeVar) -> TypeLevelExpr) -> Self {
+        Self {
+            alpha: self.alpha.as_ref().map(|s| s.map_vars(f)),
+            omega: self.omega.as_ref().map(|s| s.map_vars(f)),
+            output: self.output.as_ref().map(|o| o.map_vars(f)),
+        }
+    }
+    /// The type of a dyadic verb of this type after fixing one of its arguments
+    fn bind(&self, side: Side, arg: &Known) -> Result<TType, TypeCheckErrKind> {
+        let (spec, rest) = match side {
+            Side::Alpha => (&self.alpha, Side::Omega),
+            Side::Omega => (&self.omega, Side::Alpha),
+        };
+        // What the variables are bound to carries over to the other argument
+        let mut vars = Substitution::default();
+        let arg = match spec {
+            Some(spec) => {
+                spec.unify(arg, &mut vars)?;
+                &spec.refine(arg, &vars)
+            }
+            None => arg,
+        };
+        let fixed = |e: &Option<TypeLevelExpr>| e.as_ref().map(|e| e.bind(side, arg));
+        let output = self.output.as_ref().map(|o| OutputTypeSpecifier {
+            scalar: fixed(&o.scalar),
+            rank: fixed(&o.rank),
+            length: fixed(&o.length),
+            shape: fixed(&o.shape),
+            axes: fixed(&o.axes),
+        });
+        // Whatever is left is a monadic verb, taking its argument on the side it's missing
+        let remaining = rest
+            .pick(&self.alpha, &self.omega)
+            .as_ref()
+            .map(|spec| spec.map_vars(&mut |v| vars.apply(&TypeLevelExpr::Var(v.clone()))));
+        Ok(match rest {
+            Side::Alpha => TType {
+                alpha: remaining,
+                omega: None,
+                output,
+            },
+            Side::Omega => TType {
+                alpha: None,
+                omega: remaining,
+                output,
+            },
+        })
+    }
+}
+
+impl TypeLevelExpr {
+    /// Replaces every reference to an input with what describes it. `None` if it can't be described
+    fn substitute(&self, alpha: Option<&OutputTypeSpecifier>, omega: Option<&OutputTypeSpecifier>) -> Option<Self> {
+        use TypeLevelExpr as E;
+        let sub = |e: &TypeLevelExpr| e.substitute(alpha, omega).map(Box::new);
+        let side = |s: &Side| match s {
+            Side::Alpha => alpha,
+            Side::Omega => omega,
+        };
+        Some(match self {
+            E::RankOf(s) => side(s)?.rank.clone()?,
+            E::LengthOf(s) => side(s)?.length.clone()?,
+            E::ShapeOf(s) => side(s)?.shape.clone()?,
+            E::ScalarOf(s) => side(s)?.scalar.clone()?,
+            E::AxesOf(s) => side(s)?.axes.clone()?,
+            E::Add(x, y) => E::Add(sub(x)?, sub(y)?),
+            E::Sub(x, y) => E::Sub(sub(x)?, sub(y)?),
+            E::Reverse(x) => E::Reverse(sub(x)?),
+            E::Pervade(x, y) => E::Pervade(sub(x)?, sub(y)?),
+            E::Promote(x, y) => E::Promote(sub(x)?, sub(y)?),
+            E::Signed(x) => E::Signed(sub(x)?),
+            e => e.clone(),
+        })
+    }
+    /// Folds what doesn't depend on the arguments, so that e.g. `⍤-1` twice is `⍤-2`
+    fn simplified(self) -> Self {
+        use TypeLevelExpr as E;
+        match self {
+            E::Add(..) | E::Sub(..) => {
+                let (base, n) = self.offset();
+                match (base, n) {
+                    (None, n) => E::Nat(n.max(0) as u32),
+                    (Some(base), 0) => base,
+                    (Some(base), n) if n < 0 => E::Sub(Box::new(base), Box::new(E::Nat(n.unsigned_abs() as u32))),
+                    (Some(base), n) => E::Add(Box::new(base), Box::new(E::Nat(n as u32))),
+                }
+            }
+            E::Reverse(x) => match x.simplified() {
+                E::Reverse(x) => *x,
+                E::Shape(mut sh) => {
+                    sh.reverse();
+                    E::Shape(sh)
+                }
+                E::Axes(mut axes) => {
+                    axes.reverse();
+                    E::Axes(axes)
+                }
+                x => E::Reverse(Box::new(x)),
+            },
+            E::Pervade(x, y) => E::Pervade(Box::new(x.simplified()), Box::new(y.simplified())),
+            E::Promote(x, y) => E::Promote(Box::new(x.simplified()), Box::new(y.simplified())),
+            E::Signed(x) => E::Signed(Box::new(x.simplified())),
+            e => e,
+        }
+    }
+    /// `self` as something unknown plus a constant
+    fn offset(self) -> (Option<Self>, i64) {
+        use TypeLevelExpr as E;
+        match self {
+            E::Nat(n) => (None, n as i64),
+            E::Add(x, y) => match (x.offset(), y.offset()) {
+                ((None, a), (base, b)) | ((base, a), (None, b)) => (base, a + b),
+                ((Some(x), a), (Some(y), b)) => (Some(E::Add(Box::new(x), Box::new(y))), a + b),
+            },
+            E::Sub(x, y) => match *y {
+                E::Nat(b) => {
+                    let (base, a) = x.offset();
+                    (base, a - b as i64)
+                }
+                y => (Some(E::Sub(Box::new(x.simplified()), Box::new(y.simplified()))), 0),
+            },
+            e => (Some(e), 0),
+        }
+    }
+    /// Replaces every type variable with what `f` gives for it
+    fn map_vars(&self, f: &mut dyn FnMut(&TypeVar) -> TypeLevelExpr) -> TypeLevelExpr {
+        use TypeLevelExpr as E;
+        match self {
+            E::Var(v) => f(v),
+            E::Add(x, y) => E::Add(Box::new(x.map_vars(f)), Box::new(y.map_vars(f))),
+            E::Sub(x, y) => E::Sub(Box::new(x.map_vars(f)), Box::new(y.map_vars(f))),
+            E::Reverse(x) => E::Reverse(Box::new(x.map_vars(f))),
+            E::Pervade(x, y) => E::Pervade(Box::new(x.map_vars(f)), Box::new(y.map_vars(f))),
+            E::Promote(x, y) => E::Promote(Box::new(x.map_vars(f)), Box::new(y.map_vars(f))),
+            E::Signed(x) => E::Signed(Box::new(x.map_vars(f))),
+            e => e.clone(),
+        }
+    }
+    /// Replaces every reference to the `side` input with what's known about `arg`
+    fn bind(&self, side: Side, arg: &Known) -> TypeLevelExpr {
+        use TypeLevelExpr as E;
+        let b = |e: &TypeLevelExpr| Box::new(e.bind(side, arg));
+        let or_self = |v: Option<TypeLevelExpr>| v.unwrap_or_else(|| self.clone());
+        match self {
+            E::RankOf(s) if *s == side => or_self(arg.rank.map(E::Nat)),
+            E::LengthOf(s) if *s == side => or_self(arg.length.map(E::Nat)),
+            E::ShapeOf(s) if *s == side => or_self(arg.shape.clone().map(E::Shape)),
+            E::ScalarOf(s) if *s == side => or_self(arg.scalar.map(E::Scalar)),
+            E::AxesOf(s) if *s == side => or_self(arg.axes.clone().map(E::Axes)),
+            E::Add(x, y) => E::Add(b(x), b(y)),
+            E::Sub(x, y) => E::Sub(b(x), b(y)),
+            E::Reverse(x) => E::Reverse(b(x)),
+            E::Pervade(x, y) => E::Pervade(b(x), b(y)),
+            E::Promote(x, y) => E::Promote(b(x), b(y)),
+            E::Signed(x) => E::Signed(b(x)),
+            e => e.clone(),
+        }
+    }
+}
+
+/// The type of each primitive verb, made from its rule, see [`Rule`]
+fn primitive_type(p: Primitive) -> TType {
+    use TypeLevelExpr as E;
+    let b = Box::new;
+    let nat = |rank| {
+        Some(InputTypeSpecifier {
+            scalar: Some(E::Scalar(TAtomKind::Nat)),
+            rank,
+            ..Default::default()
+        })
+    };
+    let pervasive = |scalar| OutputTypeSpecifier {
+        scalar: Some(scalar),
+        rank: Some(E::Pervade(b(E::RankOf(Side::Alpha)), b(E::RankOf(Side::Omega)))),
+        length: None,
+        shape: Some(E::Pervade(b(E::ShapeOf(Side::Alpha)), b(E::ShapeOf(Side::Omega)))),
+        axes: Some(E::Pervade(b(E::AxesOf(Side::Alpha)), b(E::AxesOf(Side::Omega)))),
+    };
+    let promoted = || E::Promote(b(E::ScalarOf(Side::Alpha)), b(E::ScalarOf(Side::Omega)));
+    let with_scalar = |side, scalar| OutputTypeSpecifier {
+        scalar: Some(scalar),
+        ..OutputTypeSpecifier::same_as(side)
+    };
+    // Takes any argument on the sides its category says
+    let (alpha, omega) = match p.category() {
+        Category::Av => (true, false),
+        Category::Ov => (false, true),
+        _ => (true, true),
+    };
+    let ty = |output| TType {
+        alpha: alpha.then(InputTypeSpecifier::default),
+        omega: omega.then(InputTypeSpecifier::default),
+        output: Some(output),
+    };
+    match p.info().rule {
+        Rule::Same(side) => ty(OutputTypeSpecifier::same_as(side)),
+        // Promoting a type with itself only checks that it's numeric
+        Rule::Numeric(side) => ty(with_scalar(side, E::Promote(b(E::ScalarOf(side)), b(E::ScalarOf(side))))),
+        Rule::Signed(side) => ty(with_scalar(side, E::Signed(b(E::ScalarOf(side))))),
+        Rule::Unknown => ty(OutputTypeSpecifier::default()),
+        Rule::Promoted => ty(pervasive(promoted())),
+        Rule::SignedPromoted => ty(pervasive(E::Signed(b(promoted())))),
+        Rule::Comparison => ty(pervasive(E::Scalar(TAtomKind::Nat))),
+        Rule::Logic => TType {
+            alpha: nat(None),
+            omega: nat(None),
+            output: Some(pervasive(E::Scalar(TAtomKind::Nat))),
+        },
+        Rule::Count => TType {
+            output: TType::noun(&Known::scalar(TAtomKind::Nat)).output,
+            ..ty(OutputTypeSpecifier::default())
+        },
+        Rule::Shape => ty(OutputTypeSpecifier {
+            scalar: Some(E::Scalar(TAtomKind::Nat)),
+            rank: Some(E::Nat(1)),
+            length: Some(E::RankOf(Side::Alpha)),
+            ..Default::default()
+        }),
+        Rule::Select => TType {
+            omega: nat(None),
+            ..ty(OutputTypeSpecifier {
+                scalar: Some(E::ScalarOf(Side::Alpha)),
+                rank: Some(E::Add(b(E::Sub(b(E::RankOf(Side::Alpha)), b(E::Nat(1)))), b(E::RankOf(Side::Omega)))),
+                ..Default::default()
+            })
+        },
+        Rule::Pick => TType {
+            omega: nat(None),
+            ..ty(OutputTypeSpecifier {
+                scalar: Some(E::ScalarOf(Side::Alpha)),
+                shape: Some(E::Shape(vec![])),
+                ..Default::default()
+            })
+        },
+        Rule::Cells => TType {
+            omega: nat(Some(E::Nat(0))),
+            ..ty(OutputTypeSpecifier {
+                scalar: Some(E::ScalarOf(Side::Alpha)),
+                rank: Some(E::RankOf(Side::Alpha)),
+                axes: Some(E::AxesOf(Side::Alpha)),
+                ..Default::default()
+            })
+        },
+        Rule::Transpose => ty(OutputTypeSpecifier {
+            length: None,
+            shape: Some(E::Reverse(b(E::ShapeOf(Side::Alpha)))),
+            axes: Some(E::Reverse(b(E::AxesOf(Side::Alpha)))),
+            ..OutputTypeSpecifier::same_as(Side::Alpha)
         }),
-        ExprTokenKind::PrimArray => todo!(),
-        ExprTokenKind::ArrayName => todo!(),
-        ExprTokenKind::PrimAVerb => todo!(),
-        ExprTokenKind::PrimOVerb => todo!(),
-        ExprTokenKind::PrimDVerb => todo!(),
-        ExprTokenKind::PrimAAdverb => todo!(),
-        ExprTokenKind::PrimOAdverb => todo!(),
-        ExprTokenKind::PrimDAdverb => todo!(),
-        ExprTokenKind::AVerbName => todo!(),
-        ExprTokenKind::OVerbName => todo!(),
-        ExprTokenKind::DVerbName => todo!(),
-        ExprTokenKind::OAdverbName => todo!(),
-        ExprTokenKind::DAdverbName => todo!(),
-        ExprTokenKind::ArrayAssign => todo!(),
-        ExprTokenKind::VerbAssign => todo!(),
-        ExprTokenKind::AdverbAssign => todo!(),
-        ExprTokenKind::Parenthesized(expr_tokens) => todo!(),
-    }
-}
-pub fn typecheck_expr(e: ExprTree) -> Result<Typedef, TypeCheckErr> {
+        Rule::Range => TType {
+            alpha: nat(Some(E::Nat(0))),
+            ..ty(OutputTypeSpecifier {
+                scalar: Some(E::Scalar(TAtomKind::Nat)),
+                rank: Some(E::Nat(1)),
+                ..Default::default()
+            })
+        },
+        Rule::Derived => unreachable!("modifiers have no type on their own"),
+    }
+}
+
+pub(crate) fn typecheck_token<'src>(
+    s: &str,
+    t: &ExprToken<'src>,
+    env: &mut TypeEnv,
+) -> Result<TType, TypeCheckErr<'src>> {
+    let text = t.span.slice(s);
+    let prim = || {
+        let glyph = text.chars().next().expect("tokens are non-empty");
+        primitive_type(Primitive::from_glyph(glyph).expect("lexer only emits known primitives"))
+    };
+    match t.kind {
+        ExprTokenKind::Number => Ok(TType::noun(&Known::scalar(TAtomKind::Nat))),
+        ExprTokenKind::Char => Ok(TType::noun(&Known::scalar(TAtomKind::Char))),
+        ExprTokenKind::String => {
+            let len = unescape(&text[1..text.len() - 1]).chars().count() as u32;
+            Ok(TType::noun(&Known::new(Some(TAtomKind::Char), Some(vec![len]))))
+        }
+        ExprTokenKind::PrimAVerb | ExprTokenKind::PrimOVerb | ExprTokenKind::PrimDVerb => Ok(prim()),
+        // What comes out depends on the type of the argument, see `field_type`
+        ExprTokenKind::Field => Ok(TType::verb(Category::Av)),
+        ExprTokenKind::ArrayName
+        | ExprTokenKind::AVerbName
+        | ExprTokenKind::OVerbName
+        | ExprTokenKind::DVerbName => match env.names.get(&Ident(text.to_string())).cloned() {
+            Some(t) => Ok(env.instantiate(&t)),
+            None => Err(TypeCheckErr {
+                span: t.span,
+                kind: TypeCheckErrKind::UnknownName,
+            }),
+        },
+        ExprTokenKind::PrimArray
+        | ExprTokenKind::PrimAAdverb
+        | ExprTokenKind::PrimOAdverb
+        | ExprTokenKind::PrimDAdverb
+        | ExprTokenKind::OAdverbName
+        | ExprTokenKind::DAdverbName
+        | ExprTokenKind::Jot
+        | ExprTokenKind::Arrow
+        | ExprTokenKind::Parenthesized(_)
+        | ExprTokenKind::Train(_)
+        | ExprTokenKind::Strand(_)
+        | ExprTokenKind::Bracket(_)
+        | ExprTokenKind::Data(_)
+        | ExprTokenKind::Signature(_)
+        | ExprTokenKind::Match(_)
+        | ExprTokenKind::Then
+        | ExprTokenKind::Wildcard
+        | ExprTokenKind::Dfn { .. } => unreachable!("{:?} has no type on its own", t.kind),
+    }
+}
+
+pub(crate) fn typecheck_expr<'src>(
+    s: &str,
+    e: &ExprTree<'src>,
+    env: &mut TypeEnv,
+) -> Result<TType, TypeCheckErr<'src>> {
+    let span = e.span();
+    let err = |kind| TypeCheckErr { span, kind };
+    let noun = |env: &mut TypeEnv, e: &ExprTree<'src>| -> Result<Known, TypeCheckErr<'src>> {
+        let t = typecheck_expr(s, e, env)?;
+        match t.category() {
+            Category::A => Ok(t.known()),
+            _ => Err(TypeCheckErr {
+                span: e.span(),
+                kind: TypeCheckErrKind::NotANoun,
+            }),
+        }
+    };
     match e {
-        ExprTree::Leaf { cat, t } => todo!(),
-        ExprTree::AlphaVerbCall { verb, alpha } => todo!(),
-        ExprTree::OmegaVerbCall { verb, omega } => todo!(),
-        ExprTree::DyadicVerbCall { verb, alpha, omega } => todo!(),
-        ExprTree::Assignment { name, val } => todo!(),
-        ExprTree::AlphaAdverbCall { adverb, alpha } => todo!(),
-        ExprTree::OmegaAdverbCall { adverb, omega } => todo!(),
+        ExprTree::Leaf { t, .. } => typecheck_token(s, t, env),
+        ExprTree::AlphaVerbCall { alpha, verb } => {
+            let alpha = noun(env, alpha)?;
+            apply_verb(s, verb, Some(&alpha), None, env).map(|k| TType::noun(&k))
+        }
+        ExprTree::OmegaVerbCall { verb, omega } => {
+            check_fields(s, verb, omega, env)?;
+            let omega = noun(env, omega)?;
+            apply_verb(s, verb, None, Some(&omega), env).map(|k| TType::noun(&k))
+        }
+        ExprTree::DyadicVerbCall { verb, alpha, omega } => {
+            let alpha = noun(env, alpha)?;
+            let omega = noun(env, omega)?;
+            apply_verb(s, verb, Some(&alpha), Some(&omega), env).map(|k| TType::noun(&k))
+        }
+        ExprTree::AlphaBound { alpha, verb } => {
+            let alpha = noun(env, alpha)?;
+            typecheck_expr(s, verb, env)?.bind(Side::Alpha, &alpha).map_err(err)
+        }
+        ExprTree::OmegaBound { verb, omega } => {
+            let omega = noun(env, omega)?;
+            typecheck_expr(s, verb, env)?.bind(Side::Omega, &omega).map_err(err)
+        }
+        // A name with a signature has the type it gives, rather than that of what it's bound to
+        ExprTree::Assignment { name, val } => {
+            let ident = Ident(name.clone());
+            let signature = env.signatures.get(&ident).cloned();
+            // Dfns may refer to themselves
+            if let ExprTree::Dfn { cat, .. } = **val {
+                env.names.insert(ident.clone(), signature.clone().unwrap_or_else(|| TType::verb(cat)));
+            }
+            let t = typecheck_expr(s, val, env)?;
+            let t = match signature {
+                Some(signature) => {
+                    satisfies(s, val, &t, &signature, env)?;
+                    signature
+                }
+                None if env.strict && t.category() != Category::A => {
+                    let inferred = match &**val {
+                        ExprTree::Dfn { body, depth, .. } => inferred(s, body, *depth, &t, env),
+                        _ => Some(t.clone()),
+                    };
+                    return Err(err(TypeCheckErrKind::NoSignature {
+                        name: name.clone(),
+                        inferred: inferred.map(|t| env.written(Some(name), &t)),
+                    }));
+                }
+                None => t,
+            };
+            match &**val {
+                ExprTree::Dfn { body, depth, .. } if !env.signatures.contains_key(&ident) => {
+                    let dfn = BoundDfn {
+                        body: body.iter().map(ExprTree::to_static).collect(),
+                        depth: *depth,
+                        src: env.source(s),
+                    };
+                    env.dfns.insert(ident.clone(), Rc::new(dfn));
+                }
+                _ => {
+                    env.dfns.remove(&ident);
+                }
+            }
+            env.names.insert(ident, t.clone());
+            Ok(t)
+        }
+        ExprTree::Destructure { names, val, .. } => {
+            let k = noun(env, val)?;
+            if let Some(found) = k.length
+                && found != names.len() as u32
+            {
+                return Err(TypeCheckErr {
+                    span: val.span(),
+                    kind: TypeCheckErrKind::LengthMismatch {
+                        expected: names.len() as u32,
+                        found,
+                    },
+                });
+            }
+            for name in names {
+                env.names.insert(Ident(name.clone()), TType::noun(&k.major_cell()));
+            }
+            Ok(TType::noun(&k))
+        }
+        // Every item must have the same shape, as far as it's known
+        ExprTree::Bracket { items, .. } => {
+            let mut cell = Known::default();
+            for (i, item) in items.iter().enumerate() {
+                let k = noun(env, item)?;
+                let kind = match (cell.rank, k.rank, &cell.shape, &k.shape) {
+                    (Some(expected), Some(found), ..) if expected != found => {
+                        Some(TypeCheckErrKind::RankMismatch { expected, found })
+                    }
+                    (.., Some(expected), Some(found)) if expected != found => Some(TypeCheckErrKind::ShapeMismatch {
+                        expected: expected.clone(),
+                        found: found.clone(),
+                    }),
+                    _ => None,
+                };
+                if let Some(kind) = kind {
+                    return Err(TypeCheckErr { span: item.span(), kind });
+                }
+                cell = Known {
+                    scalar: if i == 0 { k.scalar } else { cell.scalar.filter(|s| Some(*s) == k.scalar) },
+                    rank: cell.rank.or(k.rank),
+                    length: None,
+                    shape: cell.shape.or(k.shape),
+                    axes: if i == 0 { k.axes } else { cell.axes.filter(|a| Some(a) == k.axes.as_ref()) },
+                };
+            }
+            Ok(TType::noun(&cell.framed(items.len() as u32)))
+        }
         ExprTree::DyadicAdverbCall {
-            adverb,
-            alpha,
-            omega,
-        } => todo!(),
+            adverb, alpha, omega, ..
+        } => match modifier_of(s, adverb) {
+            Primitive::Repeat => {
+                let cond = typecheck_expr(s, alpha, env)?;
+                if cond.category() == Category::A && !Known::scalar(TAtomKind::Nat).compatible(&cond.known()) {
+                    return Err(TypeCheckErr {
+                        span: alpha.span(),
+                        kind: TypeCheckErrKind::RepeatCount,
+                    });
+                }
+                let f = typecheck_expr(s, omega, env)?;
+                let data = match f.category() {
+                    Category::A => {
+                        return Err(TypeCheckErr {
+                            span: omega.span(),
+                            kind: TypeCheckErrKind::NotAVerb,
+                        });
+                    }
+                    Category::Av => Side::Alpha,
+                    _ => Side::Omega,
+                };
+                Ok(TType {
+                    output: Some(OutputTypeSpecifier::same_as(data)),
+                    ..f
+                })
+            }
+            Primitive::Under => {
+                let g = typecheck_expr(s, alpha, env)?;
+                let kind = match (g.category(), under_of(s, alpha)) {
+                    (Category::A, _) => Some(TypeCheckErrKind::NotAVerb),
+                    (Category::Dv, _) => Some(TypeCheckErrKind::NotMonadic),
+                    (_, Some(None)) => Some(TypeCheckErrKind::NoInverse),
+                    _ => None,
+                };
+                if let Some(kind) = kind {
+                    return Err(TypeCheckErr { span: alpha.span(), kind });
+                }
+                let f = typecheck_expr(s, omega, env)?;
+                if f.category() == Category::Dv {
+                    return Err(TypeCheckErr {
+                        span: omega.span(),
+                        kind: TypeCheckErrKind::NotMonadic,
+                    });
+                }
+                // What comes out depends on both functions, see `under_type`
+                Ok(TType { output: None, ..g })
+            }
+            Primitive::Jot => {
+                let f = typecheck_expr(s, alpha, env)?;
+                let g = typecheck_expr(s, omega, env)?;
+                let kind = match (f.category(), g.category()) {
+                    (Category::A, Category::A) => TypeCheckErrKind::NotAVerb,
+                    (Category::A, Category::Dv) => return g.bind(Side::Alpha, &f.known()).map_err(err),
+                    (Category::Dv, Category::A) => return f.bind(Side::Omega, &g.known()).map_err(err),
+                    (Category::A, _) | (_, Category::A) => TypeCheckErrKind::NotDyadic,
+                    (Category::Dv, Category::Dv) => TypeCheckErrKind::NotMonadic,
+                    _ => return Ok(compose(&f, &g)),
+                };
+                Err(err(kind))
+            }
+            p => unreachable!("{p:?} is not a dyadic modifier"),
+        },
+        ExprTree::OmegaAdverbCall { adverb, omega, .. } => {
+            let f = typecheck_expr(s, omega, env)?;
+            let any = || Some(InputTypeSpecifier::default());
+            let kind = match (modifier_of(s, adverb), f.category()) {
+                (_, Category::A) => Some(TypeCheckErrKind::NotAVerb),
+                (Primitive::Table, Category::Av | Category::Ov) => Some(TypeCheckErrKind::NotDyadic),
+                _ => None,
+            };
+            if let Some(kind) = kind {
+                return Err(TypeCheckErr {
+                    span: omega.span(),
+                    kind,
+                });
+            }
+            // What comes out depends on the arguments' shapes, see `apply_verb`
+            Ok(match modifier_of(s, adverb) {
+                Primitive::Table => TType {
+                    alpha: any(),
+                    omega: any(),
+                    output: None,
+                },
+                Primitive::Evert => TType {
+                    alpha: f.alpha.and(any()),
+                    omega: f.omega.and(any()),
+                    output: None,
+                },
+                p => unreachable!("{p:?} is not an omega-monadic modifier"),
+            })
+        }
+        ExprTree::AlphaAdverbCall { adverb, .. } => {
+            unreachable!("{:?} is not an alpha-monadic modifier", modifier_of(s, adverb))
+        }
+        ExprTree::AlphaOperand { .. } | ExprTree::OmegaOperand { .. } => {
+            unreachable!("parser rejects incomplete expressions")
+        }
+        // Nothing is known about the arguments, but the body must still make sense
+        ExprTree::Dfn { body, cat, depth, .. } => {
+            let out = dfn_type(s, body, *depth, &Known::default(), &Known::default(), env)?;
+            Ok(TType {
+                output: TType::noun(&out).output,
+                ..TType::verb(*cat)
+            })
+        }
+        // Only the scalar type depends on what the items are
+        ExprTree::Strand { items, .. } => {
+            let items = items.iter().map(|t| noun(env, t)).collect::<Result<Vec<_>, _>>()?;
+            let scalar = match &items[..] {
+                [first, rest @ ..] if first.rank == Some(0) && rest.iter().all(|k| k == first) => first.scalar,
+                _ => None,
+            };
+            Ok(TType::noun(&Known::new(scalar, Some(vec![items.len() as u32]))))
+        }
+        // What's matched is only known from the constructors in the arms, which is enough to tell
+        // whether they cover every variant
+        ExprTree::Match { arms, span } => {
+            let x = matched_type(arms, env);
+            exhaustive(arms, &x, *span, env)?;
+            let out = match_type(s, arms, &x, *span, env)?;
+            Ok(TType {
+                output: TType::noun(&out).output,
+                ..TType::verb(Category::Av)
+            })
+        }
+        ExprTree::Train { tines, .. } => {
+            let types = tines
+                .iter()
+                .map(|t| typecheck_expr(s, t, env))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(match &types[..] {
+                [f, g] => {
+                    let fed = f.output.as_ref();
+                    let (alpha, omega) = match g.category() {
+                        Category::Av => (fed, None),
+                        _ => (None, fed),
+                    };
+                    TType {
+                        alpha: f.alpha.clone(),
+                        omega: f.omega.clone(),
+                        output: g.output.as_ref().map(|o| o.compose(alpha, omega)),
+                    }
+                }
+                // The argument is taken on the side `f` takes it, so what `h` gives is in terms of that
+                [f, g, h] if f.category() != h.category() => {
+                    let side = monadic_side(f);
+                    let arg = OutputTypeSpecifier::same_as(side);
+                    let moved = h.output.as_ref().map(|o| match monadic_side(h) {
+                        Side::Alpha => o.compose(Some(&arg), None),
+                        Side::Omega => o.compose(None, Some(&arg)),
+                    });
+                    let input = InputTypeSpecifier::merge(side.pick(&f.alpha, &f.omega), monadic_side(h).pick(&h.alpha, &h.omega));
+                    let (alpha, omega) = side.pick((input.clone(), None), (None, input));
+                    TType {
+                        alpha,
+                        omega,
+                        output: g.output.as_ref().map(|o| o.compose(f.output.as_ref(), moved.as_ref())),
+                    }
+                }
+                [f, g, h] => TType {
+                    alpha: InputTypeSpecifier::merge(&f.alpha, &h.alpha),
+                    omega: InputTypeSpecifier::merge(&f.omega, &h.omega),
+                    output: g.output.as_ref().map(|o| o.compose(f.output.as_ref(), h.output.as_ref())),
+                },
+                _ => unreachable!("parser checks the length of trains"),
+            })
+        }
+    }
+}
+
+/// Which side a monadic verb of this type takes its argument on
+fn monadic_side(t: &TType) -> Side {
+    match t.category() {
+        Category::Av => Side::Alpha,
+        _ => Side::Omega,
+    }
+}
+
+impl Side {
+    /// `alpha` if this is [`Side::Alpha`], `omega` otherwise
+    fn pick<T>(self, alpha: T, omega: T) -> T {
+        match self {
+            Side::Alpha => alpha,
+            Side::Omega => omega,
+        }
+    }
+}
+
+/// The type of `f∘g` where at most one of them is dyadic. A monadic function next to a dyadic one
+/// preprocesses the argument on its side of the jot
+fn compose(f: &TType, g: &TType) -> TType {
+    let (fs, gs) = (monadic_side(f), monadic_side(g));
+    let input = |t: &TType, side: Side| side.pick(t.alpha.clone(), t.omega.clone());
+    // The output of a monadic verb, now describing its argument as coming from `to`
+    let moved = |t: &TType, from: Side, to: Side| {
+        let arg = OutputTypeSpecifier::same_as(to);
+        t.output
+            .as_ref()
+            .map(|o| o.compose(from.pick(Some(&arg), None), from.pick(None, Some(&arg))))
+    };
+    let feed = |g: &TType, alpha: Option<OutputTypeSpecifier>, omega: Option<OutputTypeSpecifier>| {
+        g.output.as_ref().map(|o| o.compose(alpha.as_ref(), omega.as_ref()))
+    };
+    match (f.category(), g.category()) {
+        (Category::Dv, _) => TType {
+            alpha: f.alpha.clone(),
+            omega: input(g, gs),
+            output: feed(f, Some(OutputTypeSpecifier::same_as(Side::Alpha)), moved(g, gs, Side::Omega)),
+        },
+        (_, Category::Dv) => TType {
+            alpha: input(f, fs),
+            omega: g.omega.clone(),
+            output: feed(g, moved(f, fs, Side::Alpha), Some(OutputTypeSpecifier::same_as(Side::Omega))),
+        },
+        _ => TType {
+            alpha: f.alpha.clone(),
+            omega: f.omega.clone(),
+            output: feed(g, gs.pick(moved(f, fs, fs), None), gs.pick(None, moved(f, fs, fs))),
+        },
+    }
+}
+
+/// Values of the type whose constructors the arms of a `⊔` name, if they name any
+fn matched_type(arms: &[(Pattern, ExprTree)], env: &TypeEnv) -> Known {
+    arms.iter()
+        .find_map(|(p, _)| match p {
+            Pattern::Name(name) | Pattern::Variant { name, .. } => env.constructor(name),
+            _ => None,
+        })
+        .map_or_else(Known::default, |(decl, _)| Known::scalar(TAtomKind::SumType(decl.id)))
+}
+
+/// Fails with [`TypeCheckErrKind::NonExhaustive`] if some `x` matches none of the arms
+fn exhaustive<'src>(arms: &[(Pattern, ExprTree)], x: &Known, span: Sp<'src>, env: &TypeEnv) -> Result<(), TypeCheckErr<'src>> {
+    match missing_variants(arms, x, env) {
+        missing if missing.is_empty() => Ok(()),
+        missing => Err(TypeCheckErr {
+            span,
+            kind: TypeCheckErrKind::NonExhaustive(missing),
+        }),
+    }
+}
+
+/// What the arms of a `⊔` give for `x`, as far as they agree
+fn match_type<'src>(
+    s: &str,
+    arms: &[(Pattern<'src>, ExprTree<'src>)],
+    x: &Known,
+    span: Sp<'src>,
+    env: &TypeEnv,
+) -> Result<Known, TypeCheckErr<'src>> {
+    let mut out: Option<Known> = None;
+    for (pattern, body) in arms {
+        let mut locals = env.clone();
+        bind_pattern(pattern, x, &mut locals).map_err(|kind| TypeCheckErr { span, kind })?;
+        let t = typecheck_expr(s, body, &mut locals)?;
+        if t.category() != Category::A {
+            return Err(TypeCheckErr {
+                span: body.span(),
+                kind: TypeCheckErrKind::NotANoun,
+            });
+        }
+        let k = t.known();
+        out = Some(match out {
+            Some(o) => o.common(&k),
+            None => k,
+        });
+    }
+    Ok(out.unwrap_or_default())
+}
+
+/// Gives the names `pattern` binds the types of what they match in `x`
+fn bind_pattern(pattern: &Pattern, x: &Known, env: &mut TypeEnv) -> Result<(), TypeCheckErrKind> {
+    let decl = env.decl_of(x);
+    match pattern {
+        Pattern::Wildcard | Pattern::Literal(_) => Ok(()),
+        Pattern::Name(name) => {
+            if decl.is_none_or(|d| d.variant(name).is_none()) {
+                env.names.insert(Ident(name.clone()), TType::noun(x));
+            }
+            Ok(())
+        }
+        Pattern::Variant { name, fields } => {
+            let types: Vec<Known> = match decl {
+                Some(d) => {
+                    let v = d.variant(name).ok_or_else(|| TypeCheckErrKind::UnknownVariant(name.clone()))?;
+                    let types = d.variants[v].fields.iter().map(|(_, k)| k.clone()).collect::<Vec<_>>();
+                    if !fields.is_empty() && fields.len() != types.len() {
+                        return Err(TypeCheckErrKind::LengthMismatch {
+                            expected: types.len() as u32,
+                            found: fields.len() as u32,
+                        });
+                    }
+                    types
+                }
+                None if x.scalar.is_some() => return Err(TypeCheckErrKind::UnknownVariant(name.clone())),
+                None => vec![Known::default(); fields.len()],
+            };
+            fields.iter().zip(&types).try_for_each(|(p, k)| bind_pattern(p, k, env))
+        }
+        Pattern::Array(items) => items.iter().try_for_each(|p| bind_pattern(p, &x.major_cell(), env)),
+    }
+}
+
+/// Whether `pattern` matches anything described by `x`
+fn irrefutable(pattern: &Pattern, x: &Known, env: &TypeEnv) -> bool {
+    let decl = env.decl_of(x);
+    match pattern {
+        Pattern::Wildcard => true,
+        Pattern::Name(name) => decl.is_none_or(|d| d.variant(name).is_none()),
+        Pattern::Literal(_) => false,
+        // Product types only have the one variant
+        Pattern::Variant { name, fields } => decl.is_some_and(|d| {
+            d.variants.len() == 1
+                && d.variant(name).is_some()
+                && fields.iter().zip(&d.variants[0].fields).all(|(p, (_, k))| irrefutable(p, k, env))
+        }),
+        Pattern::Array(items) => {
+            x.rank.is_some_and(|r| r > 0)
+                && x.length == Some(items.len() as u32)
+                && items.iter().all(|p| irrefutable(p, &x.major_cell(), env))
+        }
+    }
+}
+
+/// What no arm covers, see [`TypeCheckErrKind::NonExhaustive`]. Nothing if too little is known about `x`
+fn missing_variants(arms: &[(Pattern, ExprTree)], x: &Known, env: &TypeEnv) -> Vec<String> {
+    if x.scalar.is_none() || arms.iter().any(|(p, _)| irrefutable(p, x, env)) {
+        return vec![];
+    }
+    let Some(decl) = env.decl_of(x) else {
+        return vec!["_".to_string()];
+    };
+    let covered = |v: usize| {
+        let name = decl.constructor(v);
+        arms.iter().any(|(p, _)| match p {
+            Pattern::Name(n) => n == name,
+            Pattern::Variant { name: n, fields } => {
+                n == name && fields.iter().zip(&decl.variants[v].fields).all(|(p, (_, k))| irrefutable(p, k, env))
+            }
+            _ => false,
+        })
+    };
+    (0..decl.variants.len())
+        .filter(|&v| !covered(v))
+        .map(|v| decl.constructor(v).to_string())
+        .collect()
+}
+
+/// The type of `x .name`, from the declaration of the type of `x`
+fn field_type(name: &str, x: &Known, env: &TypeEnv) -> Result<Known, TypeCheckErrKind> {
+    if let Some(found @ 1..) = x.rank {
+        return Err(TypeCheckErrKind::RankMismatch { expected: 0, found });
+    }
+    let id = match x.scalar {
+        Some(TAtomKind::SumType(id)) => id,
+        None => return Ok(Known::default()),
+        Some(_) => return Err(TypeCheckErrKind::NoField(name.to_string())),
+    };
+    let mut types = env.data[id.0 as usize]
+        .variants
+        .iter()
+        .flat_map(|v| &v.fields)
+        .filter(|(f, _)| f == name)
+        .map(|(_, k)| k);
+    let first = types.next().ok_or_else(|| TypeCheckErrKind::NoField(name.to_string()))?;
+    // Variants may disagree on what the field holds
+    Ok(match types.all(|k| k == first) {
+        true => first.clone(),
+        false => Known::default(),
+    })
+}
+
+/// Constructors of several fields only see their argument as a vector, so when it's written out
+/// each item is checked against its field
+fn check_fields<'src>(s: &str, verb: &ExprTree<'src>, omega: &ExprTree<'src>, env: &mut TypeEnv) -> Result<(), TypeCheckErr<'src>> {
+    let (ExprTree::Leaf { t, .. }, ExprTree::Strand { items, .. } | ExprTree::Bracket { items, .. }) = (verb, omega) else {
+        return Ok(());
+    };
+    let fields: Vec<Known> = match env.constructor(t.span.slice(s)) {
+        Some((decl, variant)) => decl.variants[variant].fields.iter().map(|(_, k)| k.clone()).collect(),
+        None => return Ok(()),
+    };
+    if fields.len() != items.len() {
+        return Ok(());
+    }
+    for (item, field) in items.iter().zip(&fields) {
+        let found = typecheck_expr(s, item, env)?.known();
+        InputTypeSpecifier::of(field).check(&found).map_err(|kind| TypeCheckErr {
+            span: item.span(),
+            kind,
+        })?;
+    }
+    Ok(())
+}
+
+/// What a dfn's body gives when its arguments are as described
+fn dfn_type<'src>(
+    s: &str,
+    body: &[ExprTree<'src>],
+    depth: usize,
+    alpha: &Known,
+    omega: &Known,
+    env: &TypeEnv,
+) -> Result<Known, TypeCheckErr<'src>> {
+    let mut locals = TypeEnv {
+        strict: false,
+        ..env.clone()
+    };
+    let (a, w) = ARGUMENTS[depth - 1];
+    for (name, k) in [(a, alpha), (w, omega)] {
+        locals.names.insert(Ident(name.to_string()), TType::noun(k));
+    }
+    let (last, init) = body.split_last().expect("dfns have at least one statement");
+    for statement in init {
+        typecheck_expr(s, statement, &mut locals)?;
+    }
+    let t = typecheck_expr(s, last, &mut locals)?;
+    match t.category() {
+        Category::A => Ok(t.known()),
+        _ => Err(TypeCheckErr {
+            span: last.span(),
+            kind: TypeCheckErrKind::NotANoun,
+        }),
+    }
+}
+
+/// Checks that `val`, of type `t`, gives what `signature` says it does when given what it says it
+/// takes. Dfns' bodies and matches' arms are checked anew with their arguments described by the
+/// signature
+fn satisfies<'src>(
+    s: &str,
+    val: &ExprTree<'src>,
+    t: &TType,
+    signature: &TType,
+    env: &TypeEnv,
+) -> Result<(), TypeCheckErr<'src>> {
+    let err = |kind| TypeCheckErr { span: val.span(), kind };
+    let found = |alpha: Option<&Known>, omega: Option<&Known>| match val {
+        ExprTree::Dfn { body, depth, .. } => {
+            let any = Known::default();
+            dfn_type(s, body, *depth, alpha.unwrap_or(&any), omega.unwrap_or(&any), env)
+        }
+        ExprTree::Match { arms, span } => {
+            let any = Known::default();
+            let x = alpha.unwrap_or(&any);
+            exhaustive(arms, x, *span, env)?;
+            match_type(s, arms, x, *span, env)
+        }
+        _ => t.apply(alpha, omega).map_err(err),
+    };
+    conforms(signature, val.span(), &found, env)
+}
+
+/// What a function gives for arguments described by the two [`Known`]s
+type Call<'a, 'src> = dyn Fn(Option<&Known>, Option<&Known>) -> Result<Known, TypeCheckErr<'src>> + 'a;
+
+/// Checks that `found` gives what `signature` says for what it says it takes, once for each scalar
+/// type its arguments' type variables may stand for and each of a few ranks of an argument that the
+/// output's rank is relative to. Errors not from `found` are at `span`
+fn conforms<'src>(
+    signature: &TType,
+    span: Sp<'src>,
+    found: &Call<'_, 'src>,
+    env: &TypeEnv,
+) -> Result<(), TypeCheckErr<'src>> {
+    let err = |kind| TypeCheckErr { span, kind };
+    let scalar_var = |spec: &Option<InputTypeSpecifier>| match spec.as_ref().and_then(|spec| spec.scalar.as_ref()) {
+        Some(TypeLevelExpr::Var(v)) => Some(v.clone()),
+        _ => None,
+    };
+    let mut vars = vec![];
+    for v in [scalar_var(&signature.alpha), scalar_var(&signature.omega)].into_iter().flatten() {
+        if !vars.contains(&v) {
+            vars.push(v);
+        }
+    }
+    let scalars = env.scalar_kinds();
+    let mut instances = vec![Substitution::default()];
+    for v in &vars {
+        let of = |k: &TAtomKind| v.classes.iter().all(|c| c.has(*k));
+        instances = instances
+            .iter()
+            .flat_map(|sub| scalars.iter().filter(|k| of(k)).map(move |k| (sub, *k)))
+            .map(|(sub, k)| {
+                let mut sub = Substitution(sub.0.clone());
+                sub.0.insert(v.clone(), TypeLevelVal::Scalar(k));
+                sub
+            })
+            .collect();
+    }
+    // An output of a rank relative to an argument's says nothing unless that rank is known, so
+    // it's checked with a few of them
+    let ranks = |spec: &Option<InputTypeSpecifier>, side: Side| {
+        let least = match signature.output.as_ref().and_then(|out| out.rank.as_ref()) {
+            Some(TypeLevelExpr::Sub(x, y)) if **x == TypeLevelExpr::RankOf(side) => match **y {
+                TypeLevelExpr::Nat(n) => Some(n),
+                _ => None,
+            },
+            Some(TypeLevelExpr::Add(x, _)) if **x == TypeLevelExpr::RankOf(side) => Some(0),
+            _ => None,
+        };
+        match (spec, least) {
+            (Some(spec), Some(least)) if spec.rank.is_none() && spec.shape.is_none() => (least..least + 3).map(Some).collect(),
+            _ => vec![None],
+        }
+    };
+    let (alpha_ranks, omega_ranks) = (ranks(&signature.alpha, Side::Alpha), ranks(&signature.omega, Side::Omega));
+    for vars in &instances {
+        let input = |spec: &Option<InputTypeSpecifier>, rank| {
+            spec.as_ref().map(|spec| spec.refine(&Known { rank, ..Default::default() }, vars))
+        };
+        for (&a, &w) in alpha_ranks.iter().flat_map(|a| omega_ranks.iter().map(move |w| (a, w))) {
+            let (alpha, omega) = (input(&signature.alpha, a), input(&signature.omega, w));
+            let declared = signature.apply(alpha.as_ref(), omega.as_ref()).map_err(err)?;
+            // Arithmetic on a variable's scalar is only fine if every scalar it stands for is a number
+            let found = found(alpha.as_ref(), omega.as_ref()).map_err(|e| match e.kind {
+                TypeCheckErrKind::NotNumeric(k)
+                    if vars.0.iter().any(|(v, found)| *found == TypeLevelVal::Scalar(k) && !v.classes.contains(&Class::Num)) =>
+                {
+                    TypeCheckErr {
+                        kind: TypeCheckErrKind::NoInstance {
+                            class: Class::Num,
+                            found: TypeLevelVal::Scalar(k),
+                        },
+                        ..e
+                    }
+                }
+                _ => e,
+            })?;
+            if !declared.compatible(&found) {
+                return Err(err(TypeCheckErrKind::Unsatisfied {
+                    declared: Box::new(declared),
+                    found: Box::new(found),
+                }));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A signature for a dfn bound without one, from what its body accepts and gives when checked with
+/// arguments of every scalar type and of a few ranks. `None` if what it accepts can't be written
+/// as a signature, so that none weaker than the body is offered
+fn inferred<'src>(s: &str, body: &[ExprTree<'src>], depth: usize, t: &TType, env: &TypeEnv) -> Option<TType> {
+    use TypeLevelExpr as E;
+    const RANKS: [u32; 4] = [0, 1, 2, 3];
+    let scalars = env.scalar_kinds();
+    let any = Known::default();
+    let call = |alpha: Option<&Known>, omega: Option<&Known>| {
+        dfn_type(s, body, depth, alpha.unwrap_or(&any), omega.unwrap_or(&any), env)
+    };
+    let arg = |k: TAtomKind, r: u32| {
+        Known {
+            scalar: Some(k),
+            rank: Some(r),
+            ..Default::default()
+        }
+        .normalized()
+    };
+    let on = |side: Side, x: &Known| match side {
+        Side::Alpha => call(Some(x), None),
+        Side::Omega => call(None, Some(x)),
+    };
+    let sides = [(Side::Alpha, &t.alpha), (Side::Omega, &t.omega)]
+        .into_iter()
+        .filter_map(|(side, spec)| spec.as_ref().map(|_| side))
+        .collect::<Vec<_>>();
+
+    // What each argument may be on its own, which must be any of some scalar types at any of some
+    // ranks for it to be written
+    let mut kinds = vec![];
+    let mut ranks = vec![];
+    for &side in &sides {
+        let ok = scalars
+            .iter()
+            .flat_map(|&k| RANKS.map(|r| (k, r)))
+            .filter(|&(k, r)| on(side, &arg(k, r)).is_ok())
+            .collect::<Vec<_>>();
+        let mut ks = ok.iter().map(|&(k, _)| k).collect::<Vec<_>>();
+        ks.dedup();
+        let rs = RANKS.into_iter().filter(|r| ok.iter().any(|(_, s)| s == r)).collect::<Vec<_>>();
+        if ks.is_empty() || ok.len() != ks.len() * rs.len() {
+            return None;
+        }
+        kinds.push(ks);
+        ranks.push(rs);
+    }
+    if ranks.iter().any(|rs| rs.len() != 1 && rs.len() != RANKS.len()) {
+        return None;
+    }
+    // The scalar type of an argument: itself if it's the only one, or else a variable constrained
+    // to the widest class of which the body takes every instance
+    let widest = |ks: &mut Vec<TAtomKind>, name: &str| {
+        let var = |classes| {
+            E::Var(TypeVar {
+                name: name.to_string(),
+                instance: 0,
+                classes,
+            })
+        };
+        Some(match &ks[..] {
+            [k] => E::Scalar(*k),
+            _ if ks.len() == scalars.len() => var(vec![]),
+            _ => {
+                let class = [Class::Eq, Class::Ord, Class::Num]
+                    .into_iter()
+                    .find(|c| scalars.iter().filter(|&&k| c.has(k)).all(|k| ks.contains(k)))?;
+                ks.retain(|&k| class.has(k));
+                var(vec![class])
+            }
+        })
+    };
+    let mut scalar = vec![];
+    let mut same_scalar = false;
+    let mut same_rank = false;
+    if let ([ka, kw], [ra, rw]) = (&mut kinds[..], &ranks[..]) {
+        // Each on its own was checked with the other unknown, so what they take together is narrower
+        let at = |k: (TAtomKind, TAtomKind), r: (u32, u32)| call(Some(&arg(k.0, r.0)), Some(&arg(k.1, r.1))).is_ok();
+        let base = (ra[0], rw[0]);
+        let together = (ka.iter())
+            .flat_map(|&a| kw.iter().map(move |&w| (a, w)))
+            .filter(|&k| at(k, base))
+            .collect::<Vec<_>>();
+        ka.retain(|a| together.iter().any(|(b, _)| a == b));
+        kw.retain(|w| together.iter().any(|(_, b)| w == b));
+        scalar = vec![widest(ka, "T")?, widest(kw, "T′")?];
+        // Two arguments that can't be anything apart from each other must be the same
+        if !ka.iter().all(|&a| kw.iter().all(|&w| together.contains(&(a, w)))) {
+            let mut same = together.iter().filter(|(a, w)| a == w).map(|&(k, _)| k).collect::<Vec<_>>();
+            scalar = vec![widest(&mut same, "T")?; 2];
+            (*ka, *kw) = (same.clone(), same);
+            same_scalar = true;
+        }
+        let k = (ka[0], kw[0]);
+        if ra.len() > 1 && rw.len() > 1 && !ra.iter().all(|&a| rw.iter().all(|&w| at(k, (a, w)))) {
+            if !ra.iter().all(|&r| at(k, (r, r))) {
+                return None;
+            }
+            same_rank = true;
+        }
+    } else {
+        scalar.push(widest(&mut kinds[0], "T")?);
+    }
+    let rank_var = E::Var(TypeVar {
+        name: "R".to_string(),
+        instance: 0,
+        classes: vec![],
+    });
+    let inputs = (0..sides.len())
+        .map(|i| InputTypeSpecifier {
+            scalar: Some(scalar[i].clone()),
+            rank: match &ranks[i][..] {
+                [r] => Some(E::Nat(*r)),
+                _ if same_rank => Some(rank_var.clone()),
+                _ => None,
+            },
+            ..Default::default()
+        })
+        .collect::<Vec<_>>();
+
+    // What it gives for every argument the signature takes
+    let mut calls = vec![vec![]];
+    for i in 0..sides.len() {
+        calls = calls
+            .into_iter()
+            .flat_map(|args: Vec<(TAtomKind, u32)>| {
+                let (ks, rs) = (&kinds[i], &ranks[i]);
+                ks.iter().flat_map(|&k| rs.iter().map(move |&r| (k, r))).map(move |kr| {
+                    let mut args = args.clone();
+                    args.push(kr);
+                    args
+                })
+            })
+            .filter(|args| match &args[..] {
+                [a, w] => (!same_scalar || a.0 == w.0) && (!same_rank || a.1 == w.1),
+                _ => true,
+            })
+            .collect();
+    }
+    let mut outs = vec![];
+    for args in &calls {
+        let args = args.iter().map(|&(k, r)| arg(k, r)).collect::<Vec<_>>();
+        let (alpha, omega) = match (&sides[..], &args[..]) {
+            ([Side::Alpha], [x]) => (Some(x), None),
+            ([Side::Omega], [x]) => (None, Some(x)),
+            (_, [a, w]) => (Some(a), Some(w)),
+            _ => unreachable!("dfns take one or two arguments"),
+        };
+        outs.push((args.clone(), call(alpha, omega).ok()?));
+    }
+    // The output's scalar type and rank, if they're the same throughout or follow an argument's
+    let constant = |of: fn(&Known) -> Option<u32>| {
+        let first = of(&outs[0].1)?;
+        outs.iter().all(|(_, out)| of(out) == Some(first)).then_some(first)
+    };
+    let out_scalar = match outs[0].1.scalar {
+        Some(first) if outs.iter().all(|(_, out)| out.scalar == Some(first)) => Some(E::Scalar(first)),
+        _ => sides.iter().enumerate().find_map(|(i, &side)| {
+            outs.iter().all(|(args, out)| out.scalar.is_some() && out.scalar == args[i].scalar).then_some(E::ScalarOf(side))
+        }),
+    };
+    let out_rank = constant(|k| k.rank).map(E::Nat).or_else(|| {
+        sides.iter().enumerate().find_map(|(i, &side)| {
+            let offset = |(args, out): &(Vec<Known>, Known)| Some(out.rank? as i64 - args[i].rank? as i64);
+            let d = offset(&outs[0])?;
+            if !outs.iter().all(|o| offset(o) == Some(d)) {
+                return None;
+            }
+            let of = Box::new(E::RankOf(side));
+            Some(match d {
+                0 => E::RankOf(side),
+                d if d > 0 => E::Add(of, Box::new(E::Nat(d as u32))),
+                d => E::Sub(of, Box::new(E::Nat(-d as u32))),
+            })
+        })
+    });
+    let mut inputs = inputs.into_iter();
+    let signature = TType {
+        alpha: sides.contains(&Side::Alpha).then(|| inputs.next()).flatten(),
+        omega: sides.contains(&Side::Omega).then(|| inputs.next()).flatten(),
+        output: Some(OutputTypeSpecifier {
+            scalar: out_scalar,
+            rank: out_rank,
+            ..Default::default()
+        }),
+    };
+    let found = |alpha: Option<&Known>, omega: Option<&Known>| call(alpha, omega);
+    conforms(&signature, body[0].span(), &found, env).ok()?;
+    Some(signature)
+}
+
+/// The type of calling `verb`. Derived verbs may have extra checks that need to see the arguments
+fn apply_verb<'src>(
+    s: &str,
+    verb: &ExprTree<'src>,
+    alpha: Option<&Known>,
+    omega: Option<&Known>,
+    env: &mut TypeEnv,
+) -> Result<Known, TypeCheckErr<'src>> {
+    let span = verb.span();
+    let err = |kind| TypeCheckErr { span, kind };
+    let t = typecheck_expr(s, verb, env)?;
+    if t.category() == Category::A {
+        return Err(err(TypeCheckErrKind::NotAVerb));
+    }
+    let out = t.apply(alpha, omega).map_err(err)?;
+
+    // Dfns' bodies were checked knowing nothing of their arguments, so they're checked again
+    let any = Known::default();
+    let (a, w) = (alpha.unwrap_or(&any), omega.unwrap_or(&any));
+    if let ExprTree::Dfn { body, depth, .. } = verb {
+        return dfn_type(s, body, *depth, a, w, env);
+    }
+    if let ExprTree::Leaf { t, .. } = verb
+        && let ident = Ident(t.span.slice(s).to_string())
+        && let Some(dfn) = env.dfns.get(&ident).cloned()
+        && !env.calling.contains(&ident)
+    {
+        let mut locals = env.clone();
+        locals.calling.push(ident);
+        return dfn_type(&dfn.src, &dfn.body, dfn.depth, a, w, &locals).map_err(|e| match &*dfn.src == s {
+            true => e,
+            // Its spans are of an earlier source, e.g. an earlier line of the repl
+            false => err(e.kind),
+        });
+    }
+
+    if let ExprTree::Match { arms, span } = verb {
+        let x = alpha.expect("matches are alpha-monadic");
+        exhaustive(arms, x, verb.span(), env)?;
+        return match_type(s, arms, x, *span, env);
+    }
+    if let ExprTree::Leaf {
+        t: ExprToken {
+            kind: ExprTokenKind::Field,
+            span: field,
+        },
+        ..
+    } = verb
+    {
+        let x = alpha.expect("fields are alpha-monadic");
+        return field_type(&field.slice(s)[1..], x, env).map_err(err);
+    }
+
+    if let ExprTree::DyadicAdverbCall {
+        adverb,
+        alpha: g,
+        omega: f,
+        ..
+    } = verb
+        && modifier_of(s, adverb) == Primitive::Under
+    {
+        let x = alpha.or(omega).expect("derived verbs are called with their arguments");
+        return under_type(s, g, f, x, env);
+    }
+    if let ExprTree::OmegaAdverbCall { adverb, omega: f, .. } = verb {
+        let f = typecheck_expr(s, f, env)?;
+        return match modifier_of(s, adverb) {
+            Primitive::Table => {
+                let (alpha, omega) = alpha.zip(omega).expect("derived verbs are called with their arguments");
+                table_type(alpha, omega, &f).map_err(err)
+            }
+            Primitive::Evert => {
+                let everted = |x: Option<&Known>| x.map(Known::transposed);
+                let out = f.apply(everted(alpha).as_ref(), everted(omega).as_ref()).map_err(err)?;
+                Ok(out.transposed())
+            }
+            p => unreachable!("{p:?} is not an omega-monadic modifier"),
+        };
+    }
+
+    // Composed verbs check that each function can take what the previous one gives it
+    if let ExprTree::DyadicAdverbCall {
+        adverb,
+        alpha: f,
+        omega: g,
+        ..
+    } = verb
+        && modifier_of(s, adverb) == Primitive::Jot
+    {
+        let f = typecheck_expr(s, f, env)?;
+        let g = typecheck_expr(s, g, env)?;
+        return match (f.category(), g.category()) {
+            (Category::A, _) | (_, Category::A) => Ok(out),
+            (Category::Dv, _) => {
+                let omega = g.apply(monadic_side(&g).pick(omega, None), monadic_side(&g).pick(None, omega));
+                f.apply(alpha, Some(&omega.map_err(err)?)).map_err(err)
+            }
+            (_, Category::Dv) => {
+                let alpha = f.apply(monadic_side(&f).pick(alpha, None), monadic_side(&f).pick(None, alpha));
+                g.apply(Some(&alpha.map_err(err)?), omega).map_err(err)
+            }
+            _ => {
+                let x = f.apply(alpha, omega).map_err(err)?;
+                let side = monadic_side(&g);
+                g.apply(side.pick(Some(&x), None), side.pick(None, Some(&x))).map_err(err)
+            }
+        };
+    }
+
+    if let ExprTree::DyadicAdverbCall { adverb, omega: f, .. } = verb
+        && modifier_of(s, adverb) == Primitive::Repeat
+    {
+        let f = typecheck_expr(s, f, env)?;
+        let input = match f.category() {
+            Category::Av => alpha,
+            _ => omega,
+        };
+        let once = f.apply(alpha, omega).map_err(err)?;
+        if let Some(input) = input
+            && !once.compatible(input)
+        {
+            return Err(err(TypeCheckErrKind::RepeatNotClosed {
+                input: Box::new(input.clone()),
+                output: Box::new(once),
+            }));
+        }
+    }
+    Ok(out)
+}
+
+/// `⍜`'s registry entry for the function `e`, along with what's bound to it. `None` if it
+/// can't be known statically
+fn under_of<'a, 'src>(s: &str, e: &'a ExprTree<'src>) -> Option<Option<(Under, Option<&'a ExprTree<'src>>)>> {
+    let prim = |e: &ExprTree| match e {
+        ExprTree::Leaf { t, .. }
+            if matches!(
+                t.kind,
+                ExprTokenKind::PrimAVerb | ExprTokenKind::PrimOVerb | ExprTokenKind::PrimDVerb
+            ) =>
+        {
+            Primitive::from_glyph(t.span.slice(s).chars().next().expect("tokens are non-empty"))
+        }
+        _ => None,
+    };
+    match e {
+        ExprTree::Leaf { .. } => prim(e).map(|p| p.under(p.monadic_side()).map(|u| (u, None))),
+        ExprTree::AlphaBound { alpha, verb } => prim(verb).map(|p| p.under(Side::Omega).map(|u| (u, Some(&**alpha)))),
+        ExprTree::OmegaBound { verb, omega } => prim(verb).map(|p| p.under(Side::Alpha).map(|u| (u, Some(&**omega)))),
+        _ => None,
+    }
+}
+
+/// The type of `alpha ⊞f omega`: both arguments' frames, followed by what `f` gives. So are the
+/// names of the axes
+fn table_type(alpha: &Known, omega: &Known, f: &TType) -> Result<Known, TypeCheckErrKind> {
+    let out = f.apply(Some(&alpha.major_cell()), Some(&omega.major_cell()))?;
+    // Scalars have no frame
+    let frame = |k: &Known| match k.rank {
+        Some(0) => Some(vec![]),
+        _ => k.length.map(|l| vec![l]),
+    };
+    let frame_rank = |k: &Known| k.rank.map(|r| r.min(1));
+    let shape = match (frame(alpha), frame(omega), &out.shape) {
+        (Some(a), Some(w), Some(sh)) => Some([a, w, sh.clone()].concat()),
+        _ => None,
+    };
+    let rank = match (frame_rank(alpha), frame_rank(omega), out.rank) {
+        (Some(a), Some(w), Some(r)) => Some(a + w + r),
+        _ => None,
+    };
+    let frame_axes = |k: &Known| match k.rank {
+        Some(0) => Some(vec![]),
+        Some(_) => Some(vec![k.axes.as_ref().and_then(|axes| axes[0].clone())]),
+        None => None,
+    };
+    let axes = match (frame_axes(alpha), frame_axes(omega), out.rank) {
+        (Some(a), Some(w), Some(r)) => Some([a, w, out.axes.unwrap_or_else(|| vec![None; r as usize])].concat()),
+        _ => None,
+    };
+    Ok(Known {
+        scalar: out.scalar,
+        rank,
+        length: None,
+        shape,
+        axes,
+    }
+    .normalized())
+}
+
+/// The type of `x g⍜f`
+fn under_type<'src>(
+    s: &str,
+    g: &ExprTree<'src>,
+    f: &ExprTree<'src>,
+    x: &Known,
+    env: &mut TypeEnv,
+) -> Result<Known, TypeCheckErr<'src>> {
+    let err = |e: &ExprTree<'src>| {
+        let span = e.span();
+        move |kind| TypeCheckErr { span, kind }
+    };
+    let monadic = |t: &TType, x: &Known| match t.category() {
+        Category::Av => t.apply(Some(x), None),
+        _ => t.apply(None, Some(x)),
+    };
+    let selected = monadic(&typecheck_expr(s, g, env)?, x).map_err(err(g))?;
+    // Through `apply_verb`, so that dfns are checked knowing what they're given
+    let new = match typecheck_expr(s, f, env)?.category() {
+        Category::A => typecheck_expr(s, f, env)?.known(),
+        Category::Av => apply_verb(s, f, Some(&selected), None, env)?,
+        _ => apply_verb(s, f, None, Some(&selected), env)?,
+    };
+    match under_of(s, g) {
+        Some(Some((Under::Structural, _))) => {
+            if let (Some(expected), Some(found)) = (x.scalar, new.scalar)
+                && expected != found
+            {
+                return Err(err(f)(TypeCheckErrKind::ScalarMismatch { expected, found }));
+            }
+            // What's put back must fit where it came from, though scalars get put back everywhere
+            if new.rank == Some(0) {
+                return Ok(x.clone());
+            }
+            if let (Some(expected), Some(found)) = (selected.rank, new.rank)
+                && expected != found
+            {
+                return Err(err(f)(TypeCheckErrKind::RankMismatch { expected, found }));
+            }
+            if let (Some(expected), Some(found)) = (selected.length, new.length)
+                && expected != found
+            {
+                return Err(err(f)(TypeCheckErrKind::LengthMismatch { expected, found }));
+            }
+            if let (Some(expected), Some(found)) = (&selected.shape, &new.shape)
+                && expected != found
+            {
+                return Err(err(f)(TypeCheckErrKind::ShapeMismatch {
+                    expected: expected.clone(),
+                    found: found.clone(),
+                }));
+            }
+            Ok(x.clone())
+        }
+        Some(Some((Under::Inverse(p, side), bound))) => {
+            let mut inverse = primitive_type(p);
+            if let Some(bound) = bound {
+                let other = match side {
+                    Side::Alpha => Side::Omega,
+                    Side::Omega => Side::Alpha,
+                };
+                let bound = typecheck_expr(s, bound, env)?.known();
+                inverse = inverse.bind(other, &bound).map_err(err(g))?;
+            }
+            match side {
+                Side::Alpha => inverse.apply(Some(&new), None),
+                Side::Omega => inverse.apply(None, Some(&new)),
+            }
+            .map_err(err(g))
+        }
+        _ => Ok(Known::default()),
+    }
+}
+
+/// Only primitive modifiers exist for now
+fn modifier_of(s: &str, adverb: &ExprTree) -> Primitive {
+    let glyph = adverb.span().slice(s).chars().next().expect("tokens are non-empty");
+    Primitive::from_glyph(glyph).expect("lexer only emits known primitives")
+}
+
+/// Checks `src` in a fresh evaluator, for the tests below
+#[cfg(test)]
+fn check(src: &str) -> TResult<'_, Known> {
+    Evaluator::new().check(src).map(|t| t.unwrap().known())
+}
+
+#[test]
+fn numeric_typing() {
+    assert_eq!(check("1_0¬"), Ok(Known::new(Some(TAtomKind::Nat), Some(vec![2]))));
+    assert_eq!(check("(¯3)⌵"), Ok(Known::scalar(TAtomKind::Int)));
+    let err = check("'a'¬").unwrap_err();
+    assert_eq!(err.kind(), &TErrorKind::Type(TypeCheckErrKind::NotNumeric(TAtomKind::Char)));
+    let err = check("\"ab\"⌵").unwrap_err();
+    assert_eq!(err.kind(), &TErrorKind::Type(TypeCheckErrKind::NotNumeric(TAtomKind::Char)));
+    // ∧ only takes booleans
+    assert_eq!(check("1_0 ∧ 1"), Ok(Known::new(Some(TAtomKind::Nat), Some(vec![2]))));
+    let err = check("1 ∧ 'a'").unwrap_err();
+    assert!(matches!(err.kind(), TErrorKind::Type(TypeCheckErrKind::ScalarMismatch { .. })));
+}
+
+#[test]
+fn repeat_typing() {
+    assert_eq!(check("3⍣(1+) 5"), Ok(Known::scalar(TAtomKind::Nat)));
+    assert_eq!(check("\"abc\" ≍⍣⇌"), Ok(Known::new(Some(TAtomKind::Char), Some(vec![3]))));
+    // Tally turns a vector into a scalar, so it can't be iterated on it
+    let err = check("\"abc\" 2⍣≢").unwrap_err();
+    assert!(matches!(err.kind(), TErrorKind::Type(TypeCheckErrKind::RepeatNotClosed { .. })));
+    let err = check("\"ab\"⍣(1+) 5").unwrap_err();
+    assert_eq!(err.kind(), &TErrorKind::Type(TypeCheckErrKind::RepeatCount));
+}
+
+#[test]
+fn under_typing() {
+    assert_eq!(check("\"abc\" (↓1)⍜⇌"), Ok(Known::new(Some(TAtomKind::Char), Some(vec![3]))));
+    assert_eq!(check("¯⍜(+1) 5"), Ok(Known::scalar(TAtomKind::Int)));
+    let err = check("\"abc\" ≢⍜⇌").unwrap_err();
+    assert_eq!(err.kind(), &TErrorKind::Type(TypeCheckErrKind::NoInverse));
+    // Undoing these would lose what `÷` rounds off, or divide by zero
+    for src in ["5 (×2)⍜(+1)", "5 (÷2)⍜(+1)", "5 (×0)⍜(+1)", "(2×)⍜(+1) 5"] {
+        let err = check(src).unwrap_err();
+        assert_eq!(err.kind(), &TErrorKind::Type(TypeCheckErrKind::NoInverse), "{src}");
+    }
+    let err = check("\"abc\" (⊏0)⍜1").unwrap_err();
+    assert!(matches!(err.kind(), TErrorKind::Type(TypeCheckErrKind::ScalarMismatch { .. })));
+    assert_eq!(check("\"abcd\" (↑2)⍜{α⇌}"), Ok(Known::new(Some(TAtomKind::Char), Some(vec![4]))));
+    let err = check("\"abcd\" (↑2)⍜{[α ⋄ α]}").unwrap_err();
+    assert_eq!(err.kind(), &TErrorKind::Type(TypeCheckErrKind::RankMismatch { expected: 1, found: 2 }));
+}
+
+#[test]
+fn table_and_evert_typing() {
+    let matrix = Known::new(Some(TAtomKind::Nat), Some(vec![2, 3]));
+    assert_eq!(check("\"ab\" ⊞= \"abc\""), Ok(matrix.clone()));
+    assert_eq!(check("(\"ab\" ⊞= \"abc\") ⧋⇌"), Ok(matrix));
+    let err = check("\"ab\" ⊞⇌ \"abc\"").unwrap_err();
+    assert_eq!(err.kind(), &TErrorKind::Type(TypeCheckErrKind::NotDyadic));
+}
+
+#[test]
+fn train_typing() {
+    assert_eq!(check("\"abc\" ⦅≢ + ≢⦆"), Ok(Known::scalar(TAtomKind::Nat)));
+    assert_eq!(check("\"abc\" ⦅⇌ ⍉⦆"), Ok(Known::new(Some(TAtomKind::Char), Some(vec![3]))));
+    assert_eq!(check("2 ⦅+ ¯⦆ 3"), Ok(Known::scalar(TAtomKind::Int)));
+    // The outer tines of a fork are typed each with the argument on its own side
+    assert_eq!(check("⦅3∘≤ ∧ ≥∘1⦆ 1_2_5"), Ok(Known::new(Some(TAtomKind::Nat), Some(vec![3]))));
+    assert_eq!(check("\"abc\" ⦅≢ + ⇌∘≢⦆"), Ok(Known::scalar(TAtomKind::Nat)));
+    assert_eq!(check("3 ⦅⇌ - ¯⦆"), Ok(Known::scalar(TAtomKind::Int)));
+    let err = check("\"abc\" ⦅≢ + 1∘+⦆").unwrap_err();
+    assert_eq!(err.kind(), &TErrorKind::Type(TypeCheckErrKind::NotNumeric(TAtomKind::Char)));
+}
+
+#[test]
+fn jot_typing() {
+    let kind = |s| check(s).unwrap_err().kind().clone();
+    assert_eq!(check("\"abc\" ⇌∘≢"), Ok(Known::scalar(TAtomKind::Nat)));
+    assert_eq!(check("\"abc\" ⊏∘1"), Ok(Known::scalar(TAtomKind::Char)));
+    assert_eq!(check("\"ab\" ⇌∘⊏ 0"), Ok(Known::scalar(TAtomKind::Char)));
+    // Binding an argument checks it against what the verb takes on that side
+    assert!(matches!(kind("\"abc\" ⊏∘\"x\""), TErrorKind::Type(TypeCheckErrKind::ScalarMismatch { .. })));
+    assert_eq!(kind("+∘-"), TErrorKind::Type(TypeCheckErrKind::NotMonadic));
+    assert_eq!(kind("⇌∘1"), TErrorKind::Type(TypeCheckErrKind::NotDyadic));
+}
+
+#[test]
+fn dfn_typing() {
+    let kind = |s| check(s).unwrap_err().kind().clone();
+    assert_eq!(check("f ← {α×2} ⋄ 3 f"), Ok(Known::scalar(TAtomKind::Nat)));
+    assert_eq!(check("f ← {α⇌} ⋄ \"abc\" f"), Ok(Known::new(Some(TAtomKind::Char), Some(vec![3]))));
+    // Bodies are checked again with what each call passes them
+    assert_eq!(kind("f ← {α×2} ⋄ 'a' f"), TErrorKind::Type(TypeCheckErrKind::NotNumeric(TAtomKind::Char)));
+    assert_eq!(kind("f ← {α×2} ⋄ 3 f ⋄ 'a' f"), TErrorKind::Type(TypeCheckErrKind::NotNumeric(TAtomKind::Char)));
+    assert_eq!(kind("'a' {α×2}"), TErrorKind::Type(TypeCheckErrKind::NotNumeric(TAtomKind::Char)));
+    // Recursive calls are left to the outer one
+    assert!(check("f ← {α ⊔⟨ 0 → 0 ⋄ n → (n-1) f ⟩} ⋄ 3 f").is_ok());
+}
+
+#[test]
+fn strand_typing() {
+    assert_eq!(check("1_2_3"), Ok(Known::new(Some(TAtomKind::Nat), Some(vec![3]))));
+    assert_eq!(check("1_'a'"), Ok(Known::new(None, Some(vec![2]))));
+    assert_eq!(check("x ← 1_(2_3)_\"abc\" ⋄ x ⇌"), Ok(Known::new(None, Some(vec![3]))));
+}
+
+#[test]
+fn bracket_typing() {
+    let kind = |s| check(s).unwrap_err().kind().clone();
+    assert_eq!(check("[1_2 ⋄ 3_4 ⋄ 5_6]"), Ok(Known::new(Some(TAtomKind::Nat), Some(vec![3, 2]))));
+    assert_eq!(check("[a⋄b] ← [\"ab\" ⋄ \"cd\"] ⋄ b"), Ok(Known::new(Some(TAtomKind::Char), Some(vec![2]))));
+    assert_eq!(
+        kind("[1_2 ⋄ 3_4_5]"),
+        TErrorKind::Type(TypeCheckErrKind::ShapeMismatch {
+            expected: vec![2],
+            found: vec![3]
+        })
+    );
+    // The length of what's destructured is checked before running anything
+    assert_eq!(
+        kind("[a⋄b] ← 1_2_3"),
+        TErrorKind::Type(TypeCheckErrKind::LengthMismatch { expected: 2, found: 3 })
+    );
+}
+
+#[test]
+fn data_typing() {
+    let decls = "□ Suit ← ⟨ Club ⋄ Heart ⟩ ⋄ □ Card ← ⟨ value: 'Nat ⋄ suit: 'Suit ⟩ ⋄ □ Name ← ⟨ name: '(1, char) ⟩\n";
+    let check = |s: &str| {
+        let src = format!("{decls}{s}");
+        Evaluator::new().check(&src).map(|t| t.unwrap().known()).map_err(|e| e.kind().clone())
+    };
+    let kind = |s: &str| check(s).unwrap_err();
+    let suit = Known::scalar(TAtomKind::SumType(DataId(0)));
+    assert_eq!(check("(Card 1_Heart) .suit"), Ok(suit));
+    assert_eq!(
+        check("(Name \"ab\") .name"),
+        Ok(Known {
+            scalar: Some(TAtomKind::Char),
+            rank: Some(1),
+            length: None,
+            shape: None,
+            axes: None
+        })
+    );
+    assert_eq!(
+        kind("Card [Heart ⋄ 1]"),
+        TErrorKind::Type(TypeCheckErrKind::ScalarMismatch {
+            expected: TAtomKind::Nat,
+            found: TAtomKind::SumType(DataId(0))
+        })
+    );
+    assert_eq!(kind("Card 1_2_3"), TErrorKind::Type(TypeCheckErrKind::LengthMismatch { expected: 2, found: 3 }));
+    assert_eq!(kind("Heart .value"), TErrorKind::Type(TypeCheckErrKind::NoField("value".to_string())));
+    assert_eq!(kind("□ Hand ← ⟨ cards: '(1, Cards) ⟩"), TErrorKind::Type(TypeCheckErrKind::UnknownType("Cards".to_string())));
+}
+
+#[test]
+fn match_typing() {
+    let decls = "□ Shape ← ⟨ Circle ← ⟨ r: 'Nat ⟩ ⋄ Rect ← ⟨ w: 'Nat ⋄ h: 'Nat ⟩ ⋄ Empty ⟩\n";
+    let check = |s: &str| {
+        let src = format!("{decls}{s}");
+        Evaluator::new().check(&src).map(|t| t.unwrap().known()).map_err(|e| e.kind().clone())
+    };
+    let kind = |s: &str| check(s).unwrap_err();
+    assert_eq!(check("(Rect 2_3) ⊔⟨ Rect w h → w ⋄ _ → 0 ⟩"), Ok(Known::scalar(TAtomKind::Nat)));
+    assert_eq!(check("1_2 ⊔⟨ [a ⋄ b] → \"ab\" ⟩"), Ok(Known::new(Some(TAtomKind::Char), Some(vec![2]))));
+    let missing = |vs: &[&str]| TErrorKind::Type(TypeCheckErrKind::NonExhaustive(vs.iter().map(|v| v.to_string()).collect()));
+    assert_eq!(kind("Empty ⊔⟨ Circle r → r ⟩"), missing(&["Rect", "Empty"]));
+    assert_eq!(kind("Empty ⊔⟨ Circle 0 → 1 ⋄ Rect w h → w ⋄ Empty → 0 ⟩"), missing(&["Circle"]));
+    assert_eq!(kind("3 ⊔⟨ 3 → 1 ⟩"), missing(&["_"]));
+    // Also when bound rather than applied, from the constructors or the signature
+    assert_eq!(kind("area ← ⊔⟨ Circle r → r ⟩"), missing(&["Rect", "Empty"]));
+    assert_eq!(kind(": 'Shape » area ↳ 'Nat ⋄ area ← ⊔⟨ Circle r → r ⋄ Empty → 0 ⟩"), missing(&["Rect"]));
+    assert_eq!(kind(": 'Nat » one ↳ 'Nat ⋄ one ← ⊔⟨ 1 → 1 ⟩"), missing(&["_"]));
+    assert_eq!(check(": 'Shape » area ↳ 'Nat ⋄ area ← ⊔⟨ Circle r → r ⋄ _ → 0 ⟩ ⋄ Empty area"), Ok(Known::scalar(TAtomKind::Nat)));
+    assert_eq!(
+        kind("Empty ⊔⟨ Square s → s ⋄ _ → 0 ⟩"),
+        TErrorKind::Type(TypeCheckErrKind::UnknownVariant("Square".to_string()))
+    );
+    assert_eq!(
+        kind("Empty ⊔⟨ Rect w → w ⋄ _ → 0 ⟩"),
+        TErrorKind::Type(TypeCheckErrKind::LengthMismatch { expected: 2, found: 1 })
+    );
+}
+
+#[test]
+fn generic_typing() {
+    let sigs = ": ∀T 'T » Same « 'T ↳ 'T ⋄ Same ← {α}
+: ∀S '(⍴S, Nat) » Plus « '(⍴S, Nat) ↳ '(⍴S, Nat) ⋄ Plus ← {α+ω}
+: ∀T N '(≢N, T) » Pad
+    ↳ '(≢=5, T)
+Pad ← {α}\n";
+    let check = |s: &str| {
+        let src = format!("{sigs}{s}");
+        Evaluator::new().check(&src).map(|t| t.unwrap().known()).map_err(|e| e.kind().clone())
+    };
+    let conflict = |var: &str, first, second| {
+        TErrorKind::Type(TypeCheckErrKind::Conflict {
+            var: var.to_string(),
+            first: Box::new(first),
+            second: Box::new(second),
+        })
+    };
+    assert_eq!(check("'a' Same 'b'"), Ok(Known::scalar(TAtomKind::Char)));
+    assert_eq!(check("(1_2 Plus 3_4) Plus 5_6"), Ok(Known::new(Some(TAtomKind::Nat), Some(vec![2]))));
+    assert_eq!(
+        check("\"abc\" Pad"),
+        Ok(Known {
+            scalar: Some(TAtomKind::Char),
+            rank: None,
+            length: Some(5),
+            shape: None,
+            axes: None
+        })
+    );
+    // Each call instantiates the variables anew
+    assert_eq!(check("c ← 'a' Same 'b' ⋄ 1 Same 2"), Ok(Known::scalar(TAtomKind::Nat)));
+    use TypeLevelVal as V;
+    assert_eq!(check("1 Same 'a'"), Err(conflict("T", V::Scalar(TAtomKind::Nat), V::Scalar(TAtomKind::Char))));
+    assert_eq!(check("1_2 Plus 1_2_3"), Err(conflict("S", V::Shape(vec![2]), V::Shape(vec![3]))));
+    // What binding one argument fixes carries over to the other
+    assert!(matches!(check("'a' Same∘1"), Err(TErrorKind::Type(TypeCheckErrKind::ScalarMismatch { .. }))));
+    assert_eq!(
+        check(": ∀T 'U » Bad ↳ 'T"),
+        Err(TErrorKind::Type(TypeCheckErrKind::UnknownType("U".to_string())))
+    );
+}
+
+#[test]
+fn axis_typing() {
+    let sigs = ": '(⍴[rows, cols], Nat) » Grid ↳ '(⍴[rows=2, cols=2], Nat) ⋄ Grid ← {α}
+: '(⍴[rows=3, cols], Nat) » Three ↳ '(⍴[rows=3, cols], Nat) ⋄ Three ← {α}
+s ← [1_2 ⋄ 3_4] Grid\n";
+    let check = |s: &str| {
+        let src = format!("{sigs}{s}");
+        Evaluator::new().check(&src).map(|t| t.unwrap().known().to_string()).map_err(|e| e.to_string())
+    };
+    let err = |s: &str| Evaluator::new().check(&format!("{sigs}{s}")).unwrap_err().to_string();
+    assert_eq!(check("s ⍉"), Ok("[cols=2, rows=2] Nat".to_string()));
+    assert_eq!(check("s + 1"), Ok("[rows=2, cols=2] Nat".to_string()));
+    assert_eq!(check("s ⊞+ s"), Ok("[rows=2, rows=2, cols=2] Nat".to_string()));
+    assert_eq!(check("[s ⋄ s]"), Ok("[2, rows=2, cols=2] Nat".to_string()));
+    assert_eq!(check("((3⇡) ⊞+ (4⇡)) Three"), Ok("[rows=3, cols=?] Nat".to_string()));
+    // The sizes agree, but not which axis is which
+    assert!(err("s + (s ⍉)").starts_with("expected axes [rows=2, cols=2] Nat, found [cols=2, rows=2] Nat"));
+    assert!(err("(s ⍉) Grid").starts_with("expected axes [rows=?, cols=?] Nat, found [cols=2, rows=2] Nat"));
+}
+
+#[test]
+fn constraint_typing() {
+    let sigs = ": ∀T R (Num T) '(⍤R, T) » Double ↳ '(⍤R, T) ⋄ Double ← {α+α}
+: ∀T (Ord T) 'T » Max « 'T ↳ 'T ⋄ Max ← {α}
+: ∀T (Num T) Neg « 'T ↳ 'T ⋄ Neg ← {ω}\n";
+    let check = |s: &str| {
+        let src = format!("{sigs}{s}");
+        Evaluator::new().check(&src).map(|t| t.unwrap().known()).map_err(|e| e.kind().clone())
+    };
+    let no_instance = |class, found| TErrorKind::Type(TypeCheckErrKind::NoInstance { class, found });
+    let nats = Known {
+        scalar: Some(TAtomKind::Nat),
+        rank: Some(1),
+        ..Default::default()
+    };
+    assert_eq!(check("1_2_3 Double"), Ok(nats.clone()));
+    assert_eq!(check("'a' Max 'b'"), Ok(Known::scalar(TAtomKind::Char)));
+    assert_eq!(check("Neg 1"), Ok(Known::scalar(TAtomKind::Nat)));
+    let char = TypeLevelVal::Scalar(TAtomKind::Char);
+    assert_eq!(check("\"abc\" Double"), Err(no_instance(Class::Num, char.clone())));
+    assert_eq!(check("Neg 'a'"), Err(no_instance(Class::Num, char.clone())));
+    // Bodies are checked for every scalar a variable may stand for
+    assert_eq!(check(": ∀T '(1, T) » Inc ↳ '(1, T) ⋄ Inc ← {α+1}"), Err(no_instance(Class::Num, char)));
+    assert_eq!(check(": ∀T (Num T) '(1, T) » Inc ↳ '(1, T) ⋄ Inc ← {α+1} ⋄ 1_2 Inc"), Ok(nats));
+    let unsatisfied = check(": ∀T (Num T) '(1, T) » Sub ↳ '(1, T) ⋄ Sub ← {α-α}");
+    assert!(matches!(unsatisfied, Err(TErrorKind::Type(TypeCheckErrKind::Unsatisfied { .. }))));
+    assert_eq!(
+        check(": ∀T (Show T) 'T » Bad ↳ 'T"),
+        Err(TErrorKind::Type(TypeCheckErrKind::UnknownClass("Show".to_string())))
+    );
+}
+
+#[test]
+fn signature_checking() {
+    let kind = |s: &str| Evaluator::new().check(s).map(|_| ()).map_err(|e| e.kind().clone());
+    let unsatisfied = kind(": '(1, Nat) » Rows ↳ '(2, Nat) ⋄ Rows ← {α}");
+    assert!(matches!(unsatisfied, Err(TErrorKind::Type(TypeCheckErrKind::Unsatisfied { .. }))));
+    assert_eq!(kind(": '(≢=3, Nat) » Rev ↳ '(≢=3, Nat) ⋄ Rev ← {α⍉}"), Ok(()));
+    assert_eq!(
+        kind(": 'Nat » F ↳ 'Nat ⋄ G ← {α}"),
+        Err(TErrorKind::Type(TypeCheckErrKind::Unbound("F".to_string())))
+    );
+    let strict = |s: &str| Evaluator::new().with_signatures_required().check(s).map(|_| ()).map_err(|e| e.to_string());
+    assert_eq!(strict("x ← 1 ⋄ : Neg « 'Nat ↳ 'Nat ⋄ Neg ← {ω}"), Ok(()));
+    let no_signature = strict("F ← {α+1}").unwrap_err();
+    let inferred = ": ∀T R (Num T) '(⍤R, T) » F ↳ '(⍤R, T)";
+    assert!(no_signature.starts_with(&format!("no signature for F, which could be `{inferred}`")));
+    // What's offered is as strict as the body
+    let pasted = format!("{inferred}\nF ← {{α+1}} ⋄ 'a' F");
+    let err = Evaluator::new().check(&pasted).unwrap_err();
+    assert!(matches!(err.kind(), TErrorKind::Type(TypeCheckErrKind::NoInstance { class: Class::Num, .. })));
+    let mut e = Evaluator::new();
+    e.check("Pair ← {[α ⋄ α]} ⋄ Eq ← {α=ω}").unwrap();
+    assert_eq!(e.signature_of("Pair").unwrap(), ": ∀T '(⍤, T) » Pair ↳ '(⍤+1, T)");
+    assert_eq!(e.signature_of("Eq").unwrap(), ": ∀T R T′ '(⍤R, T) » Eq « '(⍤R, T′) ↳ '(⍤R, Nat)");
+    let mut e = Evaluator::new();
+    e.check(": ∀T R '(⍤R, T) » Id ↳ '(⍤R, T) ⋄ Id ← {α} ⋄ Tr ← ⍉").unwrap();
+    assert_eq!(e.signature_of("Id").unwrap(), ": ∀T R '(⍤R, T) » Id ↳ '(⍤R, T)");
+    assert_eq!(e.signature_of("Tr").unwrap(), ": ∀T R '(⍤R, ⍴, T) » Tr ↳ '(⍤R, ⍴⇌, T)");
+}
+
+#[test]
+fn relative_typing() {
+    let sigs = ": '(⍤, Nat) » Head ↳ '(⍤-1, Nat) ⋄ Head ← {α⊏0}
+: ∀T S '(⍴S, T) » Tra ↳ '(⍴S⇌, T) ⋄ Tra ← ⍉
+: '(⍤, Nat) » Pair « '(⍤, Nat) ↳ '(⍤₂+1, Nat) ⋄ Pair ← {[ω ⋄ ω]}\n";
+    let mut e = Evaluator::new();
+    let mut check = |s: &str| {
+        let src = format!("{sigs}{s}");
+        e.check(&src).map(|t| t.unwrap().known().to_string()).map_err(|e| e.to_string())
+    };
+    assert_eq!(check("[1_2 ⋄ 3_4] Head"), Ok("[?] Nat".to_string()));
+    assert_eq!(check("[1_2_3 ⋄ 4_5_6] Tra"), Ok("[3, 2] Nat".to_string()));
+    assert_eq!(check("1 Pair 1_2"), Ok("[?, ?] Nat".to_string()));
+    // Offsets add up through trains
+    check("F ← ⦅Head Head⦆").unwrap();
+    assert_eq!(e.signature_of("F").unwrap(), ": '(⍤, Nat) » F ↳ '(⍤-2, Nat)");
+    // Bodies must really give the rank they say they do
+    for src in [": '(⍤, Nat) » Avg ↳ '(⍤-1, Nat) ⋄ Avg ← {α}", ": '(⍤, Nat) » Cat « '(⍤, Nat) ↳ '(⍤₂+1, Nat) ⋄ Cat ← {ω}"] {
+        let unsatisfied = Evaluator::new().check(src).unwrap_err();
+        assert!(matches!(unsatisfied.kind(), TErrorKind::Type(TypeCheckErrKind::Unsatisfied { .. })), "{src}");
     }
-    todo!()
+    let ambiguous = Evaluator::new().check(": '(⍤, Nat) » Bad « '(⍤, Nat) ↳ '(⍤-1, Nat)").unwrap_err();
+    assert_eq!(ambiguous.kind(), &TErrorKind::Type(TypeCheckErrKind::BadRelative));
 }